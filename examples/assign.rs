@@ -0,0 +1,62 @@
+use parsley6::prelude::*;
+
+use parsley6::error::{DefaultCause, DefaultError};
+use parsley6::report::{Diagnostic, ErrorState, StreamWithErrors};
+use parsley6::stream::CharStream;
+
+type Stream<'a> = StreamWithErrors<CharStream<'a>>;
+type Error<'a> = DefaultError<Stream<'a>>;
+
+fn main() {
+    let source = "a = 1;\nb = ;\nc = 3;\nd 4;\ne = 5;\nf = x;\n";
+
+    for limit in [4, 2] {
+        let state = ErrorState::default().with_limit(limit);
+        let result = program.parse_with_errors_in(source, state);
+
+        println!("limit {limit}: {:?}", result.output);
+        for error in &result.errors {
+            println!("{}", Diagnostic::new(error, source));
+        }
+    }
+}
+
+fn program<'a>(stream: &mut Stream<'a>) -> Result<Vec<Option<(&'a str, u32)>>, Error<'a>> {
+    prefixed(
+        whitespace,
+        suffixed(assignment, whitespace).repeat().collect(),
+    )
+    .parse(stream)
+}
+
+fn assignment<'a>(stream: &mut Stream<'a>) -> Result<Option<(&'a str, u32)>, Error<'a>> {
+    chain!(name, whitespace, eat('='), whitespace, number)
+        .map(|(name, _, _, _, value)| Some((name, value)))
+        .terminated(';', || None)
+        .parse(stream)
+}
+
+fn name<'a>(stream: &mut Stream<'a>) -> Result<&'a str, Error<'a>> {
+    prefixed(
+        peek_if(char::is_ascii_alphabetic),
+        eat_while(char::is_ascii_alphabetic),
+    )
+    .with_err_cause(|| DefaultCause::label("a name"))
+    .parse(stream)
+}
+
+fn number<'a>(stream: &mut Stream<'a>) -> Result<u32, Error<'a>> {
+    prefixed(
+        peek_if(char::is_ascii_digit),
+        eat_while(char::is_ascii_digit),
+    )
+    .map(|s: &str| s.parse().unwrap())
+    .with_err_cause(|| DefaultCause::label("a number"))
+    .parse(stream)
+}
+
+fn whitespace<'a>(stream: &mut Stream<'a>) -> Result<(), Error<'a>> {
+    eat_while(char::is_ascii_whitespace)
+        .map_to(())
+        .parse(stream)
+}
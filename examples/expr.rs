@@ -0,0 +1,52 @@
+use std::ops::Range;
+
+use parsley6::prelude::*;
+
+use parsley6::error::{DefaultCause, DefaultError};
+use parsley6::pratt::{pratt, Associativity};
+use parsley6::stream::CharStream;
+
+type Error<'a> = DefaultError<CharStream<'a>>;
+
+#[allow(dead_code)] // only inspected through `Debug`
+#[derive(Debug)]
+enum Expr {
+    Number(u32),
+    Neg(Box<Expr>, Range<usize>),
+    Factorial(Box<Expr>, Range<usize>),
+    Binary(Box<Expr>, char, Box<Expr>, Range<usize>),
+}
+
+fn main() {
+    for input in ["1+2*3", "-1-2-3", "2^3^4!", "1==2==3", "-(1+2)*3", "1+"] {
+        println!("{input}: {:?}", expr.parse_complete(input));
+    }
+}
+
+fn expr<'a>(stream: &mut CharStream<'a>) -> Result<Expr, Error<'a>> {
+    let binary = |lhs, op, rhs, span| Expr::Binary(Box::new(lhs), op, Box::new(rhs), span);
+
+    pratt(atom)
+        .infix(Associativity::None, 1, eat('=').then_drop(eat('=')), binary)
+        .infix(Associativity::Left, 2, eat('+'), binary)
+        .infix(Associativity::Left, 2, eat('-'), binary)
+        .infix(Associativity::Left, 3, eat('*'), binary)
+        .infix(Associativity::Right, 4, eat('^'), binary)
+        .prefix(5, eat('-'), |_, operand, span| {
+            Expr::Neg(Box::new(operand), span)
+        })
+        .postfix(6, eat('!'), |operand, _, span| {
+            Expr::Factorial(Box::new(operand), span)
+        })
+        .parse(stream)
+}
+
+fn atom<'a>(stream: &mut CharStream<'a>) -> Result<Expr, Error<'a>> {
+    select!(
+        '(' => between(eat('('), expr, eat(')')),
+        ch if ch.is_ascii_digit() => eat_while(Ascii::is_ascii_digit)
+            .map(|s: &str| Expr::Number(s.parse().unwrap())),
+    )
+    .with_err_cause(|| DefaultCause::custom("expected an expression"))
+    .parse(stream)
+}
@@ -10,6 +10,7 @@ pub enum ParseErrorCause {
     Expected(String),
 
     ExpectedChar(char),
+    ExpectedOneOf(Vec<char>),
     ExpectedSlice(&'static str),
 
     ExpectedInSet,
@@ -39,6 +40,17 @@ impl<'a> Cause<CharStream<'a>> for ParseErrorCause {
     fn unknown() -> Self {
         Self::Unknown
     }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::ExpectedChar(a), Self::ExpectedChar(b)) => Self::ExpectedOneOf(vec![a, b]),
+            (Self::ExpectedOneOf(mut chars), Self::ExpectedChar(ch)) => {
+                chars.push(ch);
+                Self::ExpectedOneOf(chars)
+            }
+            (cause, _) => cause,
+        }
+    }
 }
 
 impl From<String> for ParseErrorCause {
@@ -68,13 +80,26 @@ fn main() {
 
     let mut stream = CharStream::new("1000000000000000000000000000000");
     let _ = dbg!(parse_value(&mut stream));
+
+    let mut stream = CharStream::new(r#"{ "a": [1, 2, 3], "b": { "c": null }, }"#);
+    let _ = dbg!(parse_value(&mut stream));
+
+    let mut stream = CharStream::new("[1, 2 3]");
+    let _ = dbg!(parse_value(&mut stream));
+
+    let mut stream = CharStream::new("[1, 2,]");
+    let _ = dbg!(parse_value(&mut stream));
 }
 
+#[allow(dead_code)] // only inspected through `Debug`
 #[derive(Debug, Clone)]
 enum Value {
     Number(i32),
     Bool(bool),
     Null,
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
 }
 
 fn parse_value<'a>(stream: &mut CharStream<'a>) -> Result<Value, ParseError<'a>> {
@@ -82,11 +107,43 @@ fn parse_value<'a>(stream: &mut CharStream<'a>) -> Result<Value, ParseError<'a>>
         't' => eat_slice("true").map_to(Value::Bool(true)).with_err_cause(|| "expected a value".into()),
         'f' => eat_slice("false").map_to(Value::Bool(false)).with_err_cause(|| "expected a value".into()),
         'n' => eat_slice("null").map_to(Value::Null).with_err_cause(|| "expected a value".into()),
+        '"' => parse_string.map(Value::String),
+        '[' => parse_array.map(Value::Array).with_err_context(|| "while parsing an array"),
+        '{' => parse_object.map(Value::Object).with_err_context(|| "while parsing an object"),
         ch if ch.is_ascii_digit() => parse_number.map(Value::Number).with_err_context(|| "while parsing a number"),
     )
     .parse(stream)
 }
 
+fn parse_array<'a>(stream: &mut CharStream<'a>) -> Result<Vec<Value>, ParseError<'a>> {
+    delimited_list(
+        eat('[').then_drop(ws),
+        parse_value.then_drop(ws),
+        eat(',').then_drop(ws),
+        eat(']'),
+    )
+    .parse(stream)
+}
+
+fn parse_object<'a>(stream: &mut CharStream<'a>) -> Result<Vec<(String, Value)>, ParseError<'a>> {
+    delimited_list(
+        eat('{').then_drop(ws),
+        chain!(
+            parse_string.then_drop(ws),
+            eat(':').then_drop(ws).drop_then(parse_value).then_drop(ws)
+        ),
+        eat(',').then_drop(ws),
+        eat('}'),
+    )
+    .parse(stream)
+}
+
+fn parse_string<'a>(stream: &mut CharStream<'a>) -> Result<String, ParseError<'a>> {
+    between(eat('"'), eat_while(|ch: &char| *ch != '"'), eat('"'))
+        .map(str::to_owned)
+        .parse(stream)
+}
+
 fn parse_number<'a>(stream: &mut CharStream<'a>) -> Result<i32, ParseError<'a>> {
     eat_while(Ascii::is_ascii_digit)
         .with_span()
@@ -96,3 +153,7 @@ fn parse_number<'a>(stream: &mut CharStream<'a>) -> Result<i32, ParseError<'a>>
         })
         .parse(stream)
 }
+
+fn ws<'a>(stream: &mut CharStream<'a>) -> Result<&'a str, ParseError<'a>> {
+    eat_while(|ch: &char| ch.is_whitespace()).parse(stream)
+}
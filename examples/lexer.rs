@@ -0,0 +1,97 @@
+use std::ops::Range;
+
+use parsley6::prelude::*;
+
+use parsley6::error::{DefaultCause, DefaultError};
+use parsley6::lexer::{Lexer, Spanned};
+use parsley6::stream::{AsToken, SliceStream};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Let,
+    Ident,
+    Number,
+    Eq,
+    Plus,
+    Semicolon,
+    Error,
+}
+
+/// A token as seen by the parser.
+#[derive(Debug, AsToken)]
+struct Token {
+    #[token(kind)]
+    kind: Kind,
+    #[token(span)]
+    span: Range<usize>,
+}
+
+type Tokens<'a> = SliceStream<'a, Token>;
+type Error<'a> = DefaultError<Tokens<'a>>;
+
+fn main() {
+    for input in [
+        "let x = 1 + 2;\nlet letter = 3;",
+        "let y = 1 # 2;",
+        "let = 4;",
+    ] {
+        let tokens: Vec<_> = lexer()
+            .lex(input)
+            .unwrap()
+            .into_iter()
+            .map(|Spanned(kind, span)| Token { kind, span })
+            .collect();
+        println!("{input:?}: {tokens:?}");
+
+        let mut stream = SliceStream::new(&tokens, input.len()..input.len());
+        match statements(&mut stream) {
+            Ok(spans) => {
+                for span in spans {
+                    println!("  statement: {:?}", &input[span]);
+                }
+            }
+            Err(err) => println!("  error: {err:?}"),
+        }
+    }
+}
+
+fn lexer<'a>() -> Lexer<'a, Kind> {
+    let ident_start = |ch: &char| ch.is_ascii_alphabetic() || *ch == '_';
+    let ident_char = |ch: &char| ch.is_ascii_alphanumeric() || *ch == '_';
+
+    Lexer::new()
+        .skip(eat_if(char::is_ascii_whitespace).drop_then(eat_while(char::is_ascii_whitespace)))
+        // Keywords are added before identifiers so that they win ties.
+        .token(eat_slice("let").map_to(Kind::Let))
+        .token(
+            eat_if(ident_start)
+                .drop_then(eat_while(ident_char))
+                .map_to(Kind::Ident),
+        )
+        .token(
+            eat_if(Ascii::is_ascii_digit)
+                .drop_then(eat_while(Ascii::is_ascii_digit))
+                .map_to(Kind::Number),
+        )
+        .token(eat('=').map_to(Kind::Eq))
+        .token(eat('+').map_to(Kind::Plus))
+        .token(eat(';').map_to(Kind::Semicolon))
+        .error_token(Kind::Error)
+}
+
+/// Parse `let` statements, returning the span of each.
+fn statements<'a>(stream: &mut Tokens<'a>) -> Result<Vec<Range<usize>>, Error<'a>> {
+    chain!(
+        eat(Kind::Let),
+        eat(Kind::Ident).with_err_cause(|| DefaultCause::custom("expected a name")),
+        eat(Kind::Eq),
+        eat(Kind::Number).separated_by(eat(Kind::Plus)).min(1),
+        eat(Kind::Semicolon),
+    )
+    .to_slice()
+    .map(|tokens: &[Token]| tokens[0].span.start..tokens[tokens.len() - 1].span.end)
+    .repeat()
+    .collect()
+    .then_drop(end())
+    .parse(stream)
+}
@@ -0,0 +1,141 @@
+//! Derive macros for `parsley6`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Index, Member, Type};
+
+/// Implement `AsToken` for a struct.
+///
+/// Mark the field holding the token with `#[token(kind)]` and the field holding its
+/// span with `#[token(span)]`. Tuple structs with two unmarked fields, such as
+/// `struct Token(Kind, Range<usize>)`, use the first field as the token and the
+/// second as the span.
+#[proc_macro_derive(AsToken, attributes(token))]
+pub fn derive_as_token(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    as_token(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A field that holds the token or its span.
+struct Field<'a> {
+    member: Member,
+    ty: &'a Type,
+}
+
+fn as_token(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span,
+                "`AsToken` can only be derived for structs",
+            ))
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "`AsToken` can only be derived for structs",
+            ))
+        }
+    };
+
+    let (kind, span) = find_fields(fields, input.ident.span())?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let (kind_ty, span_ty) = (kind.ty, span.ty);
+
+    // Point errors about the fields' types at the fields themselves.
+    let kind_member = &kind.member;
+    let kind_clone =
+        quote_spanned!(kind_ty.span()=> ::core::clone::Clone::clone(&self.#kind_member));
+    let span_member = &span.member;
+    let span_clone =
+        quote_spanned!(span_ty.span()=> ::core::clone::Clone::clone(&self.#span_member));
+
+    Ok(quote! {
+        impl #impl_generics ::parsley6::stream::AsToken for #name #ty_generics #where_clause {
+            type Token = #kind_ty;
+            type Span = #span_ty;
+
+            #[inline]
+            fn as_token(&self) -> Self::Token {
+                #kind_clone
+            }
+
+            #[inline]
+            fn as_span(&self) -> Self::Span {
+                #span_clone
+            }
+        }
+    })
+}
+
+fn find_fields(fields: &Fields, name_span: Span) -> syn::Result<(Field<'_>, Field<'_>)> {
+    let mut kind = None;
+    let mut span = None;
+
+    for (i, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        };
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("token"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let slot = if meta.path.is_ident("kind") {
+                    &mut kind
+                } else if meta.path.is_ident("span") {
+                    &mut span
+                } else {
+                    return Err(meta.error("expected `kind` or `span`"));
+                };
+
+                if slot.is_some() {
+                    return Err(meta.error("duplicate field for this part of the token"));
+                }
+
+                *slot = Some(Field {
+                    member: member.clone(),
+                    ty: &field.ty,
+                });
+                Ok(())
+            })?;
+        }
+    }
+
+    // Tuple structs like `Spanned(kind, span)` don't need attributes.
+    if let (None, None, Fields::Unnamed(unnamed)) = (&kind, &span, fields) {
+        if unnamed.unnamed.len() == 2 {
+            kind = Some(Field {
+                member: Member::Unnamed(Index::from(0)),
+                ty: &unnamed.unnamed[0].ty,
+            });
+            span = Some(Field {
+                member: Member::Unnamed(Index::from(1)),
+                ty: &unnamed.unnamed[1].ty,
+            });
+        }
+    }
+
+    match (kind, span) {
+        (Some(kind), Some(span)) => Ok((kind, span)),
+        (None, _) => Err(syn::Error::new(
+            name_span,
+            "missing a field marked with `#[token(kind)]`",
+        )),
+        (_, None) => Err(syn::Error::new(
+            name_span,
+            "missing a field marked with `#[token(span)]`",
+        )),
+    }
+}
@@ -1,15 +1,30 @@
-// pub mod alt;
+mod alt;
+mod boxed;
 mod by_ref;
 mod chain;
 mod errors;
+mod left_recursive;
+mod lookahead;
 mod map;
+mod memoize;
+mod optional;
 mod recover;
+mod recursive;
 mod repeat;
 mod select; // no exportable items
+mod trace;
 
+pub use alt::*;
+pub use boxed::*;
 pub use by_ref::*;
 pub use chain::*;
 pub use errors::*;
+pub use left_recursive::*;
+pub use lookahead::*;
 pub use map::*;
+pub use memoize::*;
+pub use optional::*;
 pub use recover::*;
+pub use recursive::*;
 pub use repeat::*;
+pub use trace::*;
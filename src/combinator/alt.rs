@@ -2,38 +2,81 @@ use std::marker::PhantomData;
 
 use derive_where::derive_where;
 
-use crate::error::{BuiltinCause, Error};
+use crate::error::Error;
 use crate::parser::Parser;
 use crate::stream::Stream;
 
-pub trait AltParsers<S, O, E>
+pub trait AltParsers<S, E>
 where
     S: Stream,
     E: Error<S>,
 {
-    fn parse_alt(&mut self, stream: &mut S) -> Result<O, E>;
+    type Output;
+
+    fn parse_alt(&mut self, stream: &mut S) -> Result<Self::Output, E>;
+}
+
+/// Keep whichever error got furthest into the stream, merging errors that got
+/// equally far.
+#[inline]
+pub(crate) fn furthest_error<S, E>(
+    best: Option<(usize, E)>,
+    position: usize,
+    err: E,
+) -> Option<(usize, E)>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    match best {
+        Some((best_position, best)) if best_position > position => Some((best_position, best)),
+        Some((best_position, best)) if best_position == position => {
+            Some((position, best.merge(err)))
+        }
+        _ => Some((position, err)),
+    }
 }
 
 macro_rules! impl_alt_parsers {
-    ($($parser:ident $n:tt),+) => {
-        impl<S, Out, Err, $($parser,)*>
-        AltParsers<S, Out, Err> for ($($parser,)*)
+    ($first:ident $first_n:tt $(, $parser:ident $n:tt)*) => {
+        impl<S, Err, $first, $($parser,)*>
+        AltParsers<S, Err> for ($first, $($parser,)*)
         where
             S: Stream,
             Err: Error<S>,
-            $($parser: Parser<S, Out, Err>,)*
+            $first: Parser<S, Err>,
+            $($parser: Parser<S, Err, Output = $first::Output>,)*
         {
-            #[allow(non_snake_case)]
+            type Output = $first::Output;
+
             #[inline]
-            fn parse_alt(&mut self, stream: &mut S) -> Result<Out, Err>
-            {
-                $(
-                    if let Ok(output) = self.$n.parse(stream) {
-                        return Ok(output);
+            fn parse_alt(&mut self, stream: &mut S) -> Result<Self::Output, Err> {
+                let checkpoint = stream.checkpoint();
+
+                let best = match self.$first_n.parse(stream) {
+                    Ok(output) => return Ok(output),
+                    Err(err) if err.is_fatal() => return Err(err),
+                    Err(err) => {
+                        let position = stream.stream_position();
+                        stream.rewind(checkpoint.clone());
+                        Some((position, err))
                     }
+                };
+
+                $(
+                    let best = match self.$n.parse(stream) {
+                        Ok(output) => return Ok(output),
+                        Err(err) if err.is_fatal() => return Err(err),
+                        Err(err) => {
+                            let position = stream.stream_position();
+                            stream.rewind(checkpoint.clone());
+                            furthest_error::<S, Err>(best, position, err)
+                        }
+                    };
                 )*
 
-                Err(Err::new(BuiltinCause::Unknown.into(), stream.peek_token_span()))
+                let (_, err) = best.expect("at least one alternative");
+                Err(err)
             }
         }
     };
@@ -47,21 +90,37 @@ impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4 }
 impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5 }
 impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6 }
 impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7 }
+impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8 }
+impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9 }
+impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10 }
+impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11 }
+impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11, M 12 }
+impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11, M 12, N 13 }
+impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11, M 12, N 13, O 14 }
+impl_alt_parsers! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11, M 12, N 13, O 14, P 15 }
 
+/// Try each parser in turn, rewinding the stream between attempts.
+///
+/// If every parser fails, the error that got furthest into the stream is returned.
+/// Errors that got equally far are combined with [`Error::merge`].
+///
+/// A [fatal](Error::is_fatal) error is returned immediately. For example, an
+/// [incomplete](Error::is_incomplete) error means the parser that failed may succeed
+/// once more input is available.
 #[macro_export]
 macro_rules! alt {
     ($($e:expr),+ $(,)?) => {
-        $crate::combinator::alt::alt_inner(($($e),*))
+        $crate::combinator::alt_inner(($($e,)+))
     };
 }
 
 #[inline]
 #[doc(hidden)]
-pub fn alt_inner<Parsers, S, O, E>(parsers: Parsers) -> Alt<Parsers, S, O, E>
+pub fn alt_inner<S, E, Parsers>(parsers: Parsers) -> Alt<S, E, Parsers>
 where
-    Parsers: AltParsers<S, O, E>,
     S: Stream,
     E: Error<S>,
+    Parsers: AltParsers<S, E>,
 {
     Alt {
         parsers,
@@ -70,18 +129,90 @@ where
 }
 
 #[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; Parsers)]
-pub struct Alt<Parsers, S, O, E> {
+pub struct Alt<S, E, Parsers> {
     parsers: Parsers,
-    _phantom: PhantomData<*const (S, O, E)>,
+    _phantom: PhantomData<*const (S, E)>,
 }
 
-impl<Parsers, S, O, E> Parser<S, O, E> for Alt<Parsers, S, O, E>
+impl<S, E, Parsers> Parser<S, E> for Alt<S, E, Parsers>
 where
-    Parsers: AltParsers<S, O, E>,
     S: Stream,
     E: Error<S>,
+    Parsers: AltParsers<S, E>,
 {
-    fn parse(&mut self, stream: &mut S) -> Result<O, E> {
+    type Output = Parsers::Output;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
         self.parsers.parse_alt(stream)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{DefaultCause, DefaultError, Expected};
+    use crate::prelude::*;
+    use crate::stream::CharStream;
+
+    type Error<'a> = DefaultError<CharStream<'a>>;
+
+    #[test]
+    fn single_arm() {
+        let mut parser = alt!(eat::<CharStream, Error>('a'));
+
+        assert_eq!(parser.parse_complete("a").ok(), Some('a'));
+        assert!(parser.parse_complete("b").is_err());
+    }
+
+    #[test]
+    fn sixteen_arms() {
+        let mut parser = alt!(
+            eat::<CharStream, Error>('a'),
+            eat('b'),
+            eat('c'),
+            eat('d'),
+            eat('e'),
+            eat('f'),
+            eat('g'),
+            eat('h'),
+            eat('i'),
+            eat('j'),
+            eat('k'),
+            eat('l'),
+            eat('m'),
+            eat('n'),
+            eat('o'),
+            eat('p'),
+        );
+
+        assert_eq!(parser.parse_complete("a").ok(), Some('a'));
+        assert_eq!(parser.parse_complete("p").ok(), Some('p'));
+
+        let err = parser.parse_complete("q").unwrap_err();
+        let DefaultCause::ExpectedOneOf(expected) = err.cause() else {
+            panic!("expected every arm, got {:?}", err.cause());
+        };
+        assert_eq!(expected.len(), 16);
+    }
+
+    #[test]
+    fn returns_the_furthest_error() {
+        let mut parser = alt!(
+            chain!(eat::<CharStream, Error>('a'), eat('b')).map_to(1),
+            chain!(eat('a'), eat('c'), eat('d')).map_to(2),
+            eat('x').map_to(3),
+        );
+
+        assert_eq!(parser.parse_complete("acd").ok(), Some(2));
+
+        let err = parser.parse_complete("acx").unwrap_err();
+        assert_eq!(*err.span(), 2..3);
+        assert_eq!(*err.cause(), DefaultCause::ExpectedToken('d'));
+
+        let err = parser.parse_complete("ax").unwrap_err();
+        assert_eq!(*err.span(), 1..2);
+        assert_eq!(
+            *err.cause(),
+            DefaultCause::ExpectedOneOf(vec![Expected::Token('b'), Expected::Token('c')])
+        );
+    }
+}
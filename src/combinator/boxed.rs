@@ -0,0 +1,150 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::error::Error;
+use crate::parser::Parser;
+use crate::stream::Stream;
+
+/// A type-erased parser.
+pub struct BoxedParser<'a, S, E, O> {
+    pub(crate) parser: Box<dyn Parser<S, E, Output = O> + 'a>,
+}
+
+impl<'a, S, E, O> BoxedParser<'a, S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    pub fn new<P>(parser: P) -> Self
+    where
+        P: Parser<S, E, Output = O> + 'a,
+    {
+        Self {
+            parser: Box::new(parser),
+        }
+    }
+}
+
+impl<S, E, O> Parser<S, E> for BoxedParser<'_, S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = O;
+
+    #[inline]
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        self.parser.parse(stream)
+    }
+}
+
+/// A type-erased parser that can be cloned.
+///
+/// Clones share the same underlying parser.
+///
+/// # Panics
+///
+/// Panics if the parser is re-entered while it is already running, for example
+/// if it is used recursively. Use [`recursive`](crate::combinator::recursive) for
+/// recursive parsers.
+pub struct RcParser<'a, S, E, O> {
+    pub(crate) parser: Rc<RefCell<dyn Parser<S, E, Output = O> + 'a>>,
+}
+
+impl<'a, S, E, O> RcParser<'a, S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    pub fn new<P>(parser: P) -> Self
+    where
+        P: Parser<S, E, Output = O> + 'a,
+    {
+        Self {
+            parser: Rc::new(RefCell::new(parser)),
+        }
+    }
+}
+
+impl<S, E, O> Clone for RcParser<'_, S, E, O> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+        }
+    }
+}
+
+impl<S, E, O> Parser<S, E> for RcParser<'_, S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = O;
+
+    #[inline]
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        self.parser
+            .try_borrow_mut()
+            .expect("shared parser re-entered while running")
+            .parse(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::combinator::Recursive;
+    use crate::error::DefaultError;
+    use crate::prelude::*;
+    use crate::stream::CharStream;
+
+    use super::*;
+
+    type Error<'a> = DefaultError<CharStream<'a>>;
+
+    #[test]
+    fn boxed_parsers_of_different_types() {
+        let mut parsers: Vec<BoxedParser<CharStream, Error, char>> = vec![
+            eat('a').boxed(),
+            eat_if(char::is_ascii_digit).boxed(),
+            chain!(eat('('), eat('b'), eat(')'))
+                .map(|(_, b, _)| b)
+                .boxed(),
+        ];
+
+        let outputs: Vec<_> = ["a", "7", "(b)"]
+            .into_iter()
+            .zip(&mut parsers)
+            .map(|(input, parser)| parser.parse_complete(input).ok())
+            .collect();
+        assert_eq!(outputs, [Some('a'), Some('7'), Some('b')]);
+    }
+
+    #[test]
+    fn rc_clones_share_the_parser() {
+        let mut count = 0;
+        let counter = eat::<CharStream, Error>('a').map(|_| {
+            count += 1;
+            count
+        });
+
+        let mut first = counter.rc();
+        let mut second = first.clone();
+        assert_eq!(first.parse_complete("a").ok(), Some(1));
+        assert_eq!(second.parse_complete("a").ok(), Some(2));
+    }
+
+    #[test]
+    #[should_panic = "shared parser re-entered while running"]
+    fn rc_panics_when_re_entered() {
+        let nested = Recursive::<CharStream, Error, ()>::declare();
+        let shared = between(eat('('), nested.clone().optional(), eat(')'))
+            .map_to(())
+            .rc();
+        nested.define(shared.clone());
+
+        let _ = shared.clone().parse_complete("(())");
+    }
+}
@@ -0,0 +1,166 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use derive_where::derive_where;
+
+use crate::combinator::{recursive, Recursive};
+use crate::error::{Cause, Error};
+use crate::parser::Parser;
+use crate::stream::Stream;
+
+/// Create a parser that can refer to itself, including as its own first item.
+///
+/// Rules such as `expr = expr '+' term | term` would recurse forever as plain
+/// parsers. Here, a left-recursive call fails at first, then the rule is re-run
+/// with the left-recursive call replaying the previous result for as long as the
+/// match keeps growing (the "seed-growing" algorithm of Warth et al.). This
+/// produces left-associative results.
+///
+/// Only direct left recursion is supported. As with [`recursive`], the definition
+/// must implement [`Clone`].
+#[inline]
+pub fn left_recursive<'a, P, F, S, E>(f: F) -> LeftRecursive<'a, S, E, P::Output>
+where
+    P: Parser<S, E> + Clone + 'a,
+    P::Output: Clone,
+    F: FnOnce(LeftRecursive<'a, S, E, P::Output>) -> P,
+    S: Stream,
+    E: Error<S> + Clone,
+{
+    let seeds = Rc::new(RefCell::new(HashMap::new()));
+
+    let body = recursive(|body| {
+        f(LeftRecursive {
+            body,
+            seeds: seeds.clone(),
+        })
+    });
+
+    LeftRecursive { body, seeds }
+}
+
+/// The result of a left-recursive rule at a position, and where it finished.
+type Seeds<S, E, O> = Rc<RefCell<HashMap<usize, (Result<O, E>, <S as Stream>::Checkpoint)>>>;
+
+/// A handle to a parser that may be defined in terms of itself.
+///
+/// Create one with [`left_recursive`].
+#[derive_where(Clone)]
+pub struct LeftRecursive<'a, S: Stream, E, O> {
+    body: Recursive<'a, S, E, O>,
+
+    /// Seeds for the positions where the rule is currently being grown.
+    seeds: Seeds<S, E, O>,
+}
+
+impl<S, E, O> Parser<S, E> for LeftRecursive<'_, S, E, O>
+where
+    S: Stream,
+    E: Error<S> + Clone,
+    O: Clone,
+{
+    type Output = O;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let start = stream.stream_position();
+
+        // A left-recursive call, which replays the current seed without undoing any
+        // changes made to the state since the rule started.
+        let seed = self.seeds.borrow().get(&start).cloned();
+        if let Some((result, end)) = seed {
+            stream.jump_to(end);
+            return result;
+        }
+
+        let checkpoint = stream.checkpoint();
+
+        // The first seed fails, so that only the non-left-recursive alternatives match.
+        let failed = E::new(E::Cause::unknown(), stream.peek_token_span());
+        self.seeds
+            .borrow_mut()
+            .insert(start, (Err(failed), checkpoint.clone()));
+
+        let mut best = match self.body.parse(stream) {
+            Ok(output) => (output, stream.checkpoint(), stream.stream_position()),
+            Err(err) => {
+                self.seeds.borrow_mut().remove(&start);
+                return Err(err);
+            }
+        };
+
+        // Grow the seed until the match stops getting longer. Each attempt starts
+        // with the state the best match left, since the seed it replays doesn't
+        // repeat its changes to the state.
+        loop {
+            self.seeds
+                .borrow_mut()
+                .insert(start, (Ok(best.0.clone()), best.1.clone()));
+            stream.jump_to(checkpoint.clone());
+
+            match self.body.parse(stream) {
+                Ok(output) if stream.stream_position() > best.2 => {
+                    best = (output, stream.checkpoint(), stream.stream_position());
+                }
+                _ => break,
+            }
+        }
+
+        self.seeds.borrow_mut().remove(&start);
+
+        // Undo the attempt that didn't grow the match.
+        let (output, end, _) = best;
+        stream.rewind(end);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::DefaultError;
+    use crate::prelude::*;
+    use crate::report::StreamWithErrors;
+    use crate::stream::CharStream;
+
+    type Stream<'a> = StreamWithErrors<CharStream<'a>>;
+    type Error<'a> = DefaultError<Stream<'a>>;
+
+    #[test]
+    fn grows_left_associatively() {
+        let mut expr = left_recursive(|expr| {
+            alt!(
+                chain!(expr, eat('-'), eat_if(char::is_ascii_digit))
+                    .map(|(lhs, _, rhs)| format!("({lhs}-{rhs})")),
+                eat_if::<_, Stream, Error>(char::is_ascii_digit).map(String::from),
+            )
+        });
+
+        let result = expr.parse_with_errors("1-2-3");
+        assert_eq!(result.output.as_deref(), Some("((1-2)-3)"));
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn keeps_errors_reported_by_the_seed() {
+        let atom = eat_if::<_, Stream, Error>(char::is_ascii_digit)
+            .or_recover(eat_if(|_: &char| true))
+            .map(String::from);
+
+        let mut expr = left_recursive(|expr| {
+            alt!(
+                chain!(expr, eat('+'), atom).map(|(lhs, _, rhs)| format!("({lhs}+{rhs})")),
+                atom,
+            )
+        });
+
+        let result = expr.parse_with_errors("x+1+x");
+        assert_eq!(result.output.as_deref(), Some("((x+1)+x)"));
+
+        let spans: Vec<_> = result
+            .errors
+            .iter()
+            .map(|error| error.cause_span())
+            .collect();
+        assert_eq!(spans, [&(0..1), &(4..5)]);
+    }
+}
@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+
+use derive_where::derive_where;
+
+use crate::error::{Cause, Error};
+use crate::parser::Parser;
+use crate::stream::{Span, Stream};
+
+/// Run a parser without consuming any input, returning its output or error.
+#[inline]
+pub fn lookahead<P, S, E>(parser: P) -> Lookahead<P, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    Lookahead {
+        parser,
+        _phantom: PhantomData,
+    }
+}
+
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P)]
+pub struct Lookahead<P, S, E> {
+    parser: P,
+    _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, S, E> Parser<S, E> for Lookahead<P, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = P::Output;
+
+    #[inline]
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let checkpoint = stream.checkpoint();
+        let result = self.parser.parse(stream);
+        stream.rewind(checkpoint);
+        result
+    }
+}
+
+/// Succeed without consuming any input if a parser fails, or return an error if it
+/// succeeds.
+///
+/// A [fatal](Error::is_fatal) error from the parser is returned as is.
+#[inline]
+pub fn not<P, S, E>(parser: P) -> Not<P, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    Not {
+        parser,
+        _phantom: PhantomData,
+    }
+}
+
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P)]
+pub struct Not<P, S, E> {
+    parser: P,
+    _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, S, E> Parser<S, E> for Not<P, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = ();
+
+    #[inline]
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let checkpoint = stream.checkpoint();
+        let start = stream.stream_position();
+        let start_span = stream.peek_token_span();
+
+        let result = self.parser.parse(stream);
+
+        // The error covers whatever the parser matched.
+        let span = if stream.stream_position() > start {
+            start_span.merge_right(stream.prev_token_span())
+        } else {
+            start_span
+        };
+        stream.rewind(checkpoint);
+
+        match result {
+            Ok(_) => Err(E::new(E::Cause::expected_predicate(), span)),
+            Err(err) if err.is_fatal() => Err(err),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{DefaultCause, DefaultError, Error as _};
+    use crate::prelude::*;
+    use crate::stream::{CharStream, Partial, Stream};
+
+    type Error<'a> = DefaultError<CharStream<'a>>;
+
+    #[test]
+    fn lookahead_never_consumes() {
+        let mut stream = CharStream::new("ab");
+
+        let result: Result<_, Error> = lookahead(eat_slice("ab")).parse(&mut stream);
+        assert_eq!(result.ok(), Some("ab"));
+        assert_eq!(stream.stream_position(), 0);
+
+        let result: Result<_, Error> = lookahead(eat_slice("ax")).parse(&mut stream);
+        assert_eq!(*result.unwrap_err().span(), 0..1);
+        assert_eq!(stream.stream_position(), 0);
+    }
+
+    #[test]
+    fn not_inverts_the_parser() {
+        // An identifier that isn't the keyword `let`.
+        let mut ident = prefixed(
+            not(chain!(
+                eat_slice("let"),
+                not(eat_if(char::is_ascii_alphabetic))
+            )),
+            eat_while::<_, CharStream, Error>(char::is_ascii_alphabetic),
+        );
+
+        assert_eq!(ident.parse_complete("letter").ok(), Some("letter"));
+
+        let err = ident.parse_complete("let").unwrap_err();
+        assert_eq!(*err.span(), 0..3);
+        assert_eq!(*err.cause(), DefaultCause::ExpectedPredicate);
+    }
+
+    #[test]
+    fn not_returns_fatal_errors() {
+        let mut stream = Partial::new(CharStream::new("le"));
+
+        let result: Result<_, DefaultError<Partial<CharStream>>> =
+            not(eat_slice("let")).parse(&mut stream);
+        assert!(result.unwrap_err().is_incomplete());
+        assert_eq!(stream.stream_position(), 0);
+    }
+}
@@ -242,11 +242,15 @@ where
     type Output = S::SliceRef;
 
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
-        let start = stream.stream_position();
-        let _ = self.parser.parse(stream)?;
-        let end = stream.stream_position();
+        // Keep the input being sliced from being discarded.
+        let start = stream.pin();
+        let result = self
+            .parser
+            .parse(stream)
+            .map(|_| stream.slice(start, stream.stream_position()));
 
-        Ok(stream.slice(start, end))
+        stream.unpin(start);
+        result
     }
 }
 
@@ -265,10 +269,15 @@ where
     type Output = (P::Output, S::SliceRef);
 
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
-        let start = stream.stream_position();
-        let output = self.parser.parse(stream)?;
-        let end = stream.stream_position();
-        Ok((output, stream.slice(start, end)))
+        // Keep the input being sliced from being discarded.
+        let start = stream.pin();
+        let result = self
+            .parser
+            .parse(stream)
+            .map(|output| (output, stream.slice(start, stream.stream_position())));
+
+        stream.unpin(start);
+        result
     }
 }
 
@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use derive_where::derive_where;
+
+use crate::error::Error;
+use crate::memo::{MemoCache, MemoState};
+use crate::parser::Parser;
+use crate::stream::{BorrowState, Rewind, Stream};
+
+/// Where the stream finished, and the state the result was cached for.
+type Entry<S> = (
+    <S as Stream>::Checkpoint,
+    <<S as BorrowState>::State as Rewind>::Checkpoint,
+);
+
+type SharedCache<O, E, S> = Rc<RefCell<MemoCache<O, E, Entry<S>>>>;
+
+#[derive_where(Clone; P)]
+pub struct Memoize<P, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream + BorrowState<State: Rewind>,
+    E: Error<S>,
+{
+    pub(crate) parser: P,
+    pub(crate) cache: SharedCache<P::Output, E, S>,
+    pub(crate) _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, S, E> Parser<S, E> for Memoize<P, S, E>
+where
+    P: Parser<S, E, Output: Clone>,
+    S: Stream + BorrowState<State: MemoState + Rewind<Checkpoint: PartialEq>>,
+    E: Error<S> + Clone,
+{
+    type Output = P::Output;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let session = stream.borrow_state().memo_session();
+        let start = stream.stream_position();
+        let state = stream.borrow_state().checkpoint();
+
+        let cached = self
+            .cache
+            .borrow_mut()
+            .entries(session)
+            .get(&start)
+            .cloned();
+        if let Some((result, (end, cached_state))) = cached {
+            if cached_state == state {
+                stream.rewind(end);
+                return result;
+            }
+        }
+
+        let result = self.parser.parse(stream);
+
+        // Rewinding to the end of a result can't redo changes to the state, so only
+        // results that made none are cached.
+        if stream.borrow_state().checkpoint() == state {
+            self.cache
+                .borrow_mut()
+                .entries(session)
+                .insert(start, (result.clone(), (stream.checkpoint(), state)));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use crate::error::DefaultError;
+    use crate::memo::MemoSession;
+    use crate::prelude::*;
+    use crate::report::StreamWithErrors;
+    use crate::stream::CharStream;
+
+    type Stream<'a> = StreamWithErrors<CharStream<'a>, MemoSession>;
+    type Error<'a> = DefaultError<Stream<'a>>;
+
+    #[test]
+    fn replays_cached_results() {
+        let runs = Cell::new(0);
+        let m = (|stream: &mut Stream<'static>| -> Result<char, Error<'static>> {
+            runs.set(runs.get() + 1);
+            eat('a').parse(stream)
+        })
+        .memoize();
+
+        let mut parser = alt!(chain!(m.clone(), eat('x')), chain!(m, eat('y')));
+
+        let result = parser.parse_with_errors("ay");
+        assert_eq!(result.output, Some(('a', 'y')));
+        assert_eq!(runs.get(), 1);
+    }
+
+    #[test]
+    fn keeps_errors_reported_before_a_cache_hit() {
+        let m = eat::<Stream, Error>('a')
+            .or_recover(eat_if(|_: &char| true))
+            .memoize();
+
+        let mut parser = alt!(chain!(m.clone(), eat('x')), chain!(m, eat('y')));
+
+        let result = parser.parse_with_errors("?y");
+        assert_eq!(result.output, Some(('?', 'y')));
+
+        let spans: Vec<_> = result
+            .errors
+            .iter()
+            .map(|error| error.cause_span())
+            .collect();
+        assert_eq!(spans, [&(0..1)]);
+    }
+}
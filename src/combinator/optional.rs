@@ -0,0 +1,80 @@
+use std::marker::PhantomData;
+
+use derive_where::derive_where;
+
+use crate::error::Error;
+use crate::parser::Parser;
+use crate::stream::Stream;
+
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P)]
+pub struct Optional<P, S, E> {
+    pub(crate) parser: P,
+    pub(crate) _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, S, E> Parser<S, E> for Optional<P, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = Option<P::Output>;
+
+    #[inline]
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let start = stream.stream_position();
+
+        match self.parser.parse(stream) {
+            Ok(output) => Ok(Some(output)),
+
+            // The parser failed part-way through, or the error is fatal.
+            Err(err) if stream.stream_position() != start || err.is_fatal() => Err(err),
+
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{DefaultCause, DefaultError};
+    use crate::prelude::*;
+    use crate::stream::{CharStream, Partial, Stream};
+
+    type Error<'a> = DefaultError<CharStream<'a>>;
+
+    fn sign<'a>() -> impl Parser<CharStream<'a>, Error<'a>, Output = Option<(char, char)>> {
+        chain!(eat('-'), eat('1')).optional()
+    }
+
+    #[test]
+    fn absent_is_none() {
+        let mut stream = CharStream::new("2");
+
+        assert_eq!(sign().parse(&mut stream).ok(), Some(None));
+        assert_eq!(stream.stream_position(), 0);
+        assert_eq!(sign().parse_complete("-1").ok(), Some(Some(('-', '1'))));
+    }
+
+    #[test]
+    fn broken_is_an_error() {
+        let err = sign().parse_complete("-2").unwrap_err();
+
+        assert_eq!(*err.span(), 1..2);
+        assert_eq!(*err.cause(), DefaultCause::ExpectedToken('1'));
+    }
+
+    #[test]
+    fn fatal_errors_are_returned() {
+        let mut stream = Partial::new(CharStream::new(""));
+
+        let result: Result<_, DefaultError<Partial<CharStream>>> =
+            eat_slice("let").optional().parse(&mut stream);
+        assert!(result.is_err());
+
+        let mut stream = stream.complete();
+        let result: Result<_, DefaultError<Partial<CharStream>>> =
+            eat_slice("let").optional().parse(&mut stream);
+        assert_eq!(result.ok(), Some(None));
+    }
+}
@@ -2,36 +2,272 @@ use std::marker::PhantomData;
 
 use derive_where::derive_where;
 
-use crate::error::{Error, Report};
+use crate::error::{Cause, Error, Report};
 use crate::parser::Parser;
-use crate::stream::{BorrowState, Stream};
+use crate::recovery::{recovered, skip_balanced, Failure, Strategy};
+use crate::stream::{BorrowState, Rewind, Span, Stream};
 
 #[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P, R)]
 pub struct OrRecover<P, R, S, E> {
     pub(crate) parser: P,
     pub(crate) recover: R,
+    pub(crate) retract: bool,
     pub(crate) _phantom: PhantomData<*const (S, E)>,
 }
 
+impl<P, R, S, E> OrRecover<P, R, S, E>
+where
+    P: Parser<S, E>,
+    R: Parser<S, E, Output = P::Output>,
+    S: Stream + BorrowState<State: Report<E> + Rewind>,
+    E: Error<S>,
+{
+    /// If recovery fails, take back the report of the original error, along with
+    /// anything reported while recovering.
+    ///
+    /// The returned error still records the span of the original error.
+    #[inline]
+    pub fn retract_on_failure(mut self) -> Self {
+        self.retract = true;
+        self
+    }
+}
+
 impl<P, R, S, E> Parser<S, E> for OrRecover<P, R, S, E>
 where
     P: Parser<S, E>,
     R: Parser<S, E, Output = P::Output>,
-    S: Stream + BorrowState<State: Report<E>>,
+    S: Stream + BorrowState<State: Report<E> + Rewind>,
     E: Error<S>,
 {
     type Output = P::Output;
 
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let start_span = stream.peek_token_span();
+        let start = stream.stream_position();
+
         match self.parser.parse(stream) {
             Ok(value) => Ok(value),
+            Err(err) if err.is_fatal() => Err(err),
             Err(err) => {
-                stream.borrow_state().report(err);
+                let span = if stream.stream_position() > start {
+                    start_span.merge_right(stream.prev_token_span())
+                } else {
+                    start_span
+                };
+
+                let reports = stream.borrow_state().checkpoint();
+                recovered(stream, err)?;
 
-                // TODO: can we find a way to report that
-                // this error occurred while recovering?
-                self.recover.by_ref().parse(stream)
+                self.recover.parse(stream).map_err(|err| {
+                    if self.retract {
+                        stream.borrow_state().rewind(reports);
+                    }
+                    err.recovering_from(span)
+                })
             }
         }
     }
 }
+
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P, R)]
+pub struct RecoverWith<P, R, S, E> {
+    pub(crate) parser: P,
+    pub(crate) strategy: R,
+    pub(crate) _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, R, S, E> Parser<S, E> for RecoverWith<P, R, S, E>
+where
+    P: Parser<S, E>,
+    R: Strategy<P, S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = P::Output;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let start = stream.checkpoint();
+        let start_span = stream.peek_token_span();
+
+        match self.parser.parse(stream) {
+            Ok(value) => Ok(value),
+            Err(error) if error.is_fatal() => Err(error),
+            Err(error) => self.strategy.recover(
+                &mut self.parser,
+                stream,
+                Failure {
+                    error,
+                    start,
+                    start_span,
+                },
+            ),
+        }
+    }
+}
+
+#[derive_where(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash; P, D, S::Token)]
+pub struct Terminated<P, D, S: Stream, E> {
+    pub(crate) parser: P,
+    pub(crate) token: S::Token,
+    pub(crate) default: D,
+    pub(crate) delimiters: Vec<(S::Token, S::Token)>,
+    pub(crate) _phantom: PhantomData<*const E>,
+}
+
+impl<P, D, S, E> Terminated<P, D, S, E>
+where
+    P: Parser<S, E>,
+    D: FnMut() -> P::Output,
+    S: Stream<Token: Clone> + BorrowState<State: Report<E>>,
+    E: Error<S>,
+{
+    /// Skip over groups delimited by `open` and `close` when seeking the terminator.
+    #[inline]
+    pub fn nested(mut self, open: S::Token, close: S::Token) -> Self {
+        self.delimiters.push((open, close));
+        self
+    }
+}
+
+impl<P, D, S, E> Parser<S, E> for Terminated<P, D, S, E>
+where
+    P: Parser<S, E>,
+    D: FnMut() -> P::Output,
+    S: Stream<Token: Clone> + BorrowState<State: Report<E>>,
+    E: Error<S>,
+{
+    type Output = P::Output;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let checkpoint = stream.checkpoint();
+        let start = stream.stream_position();
+
+        let err = match self.parser.parse(stream) {
+            Ok(value) => match stream.peek_token() {
+                Some(token) if token == self.token => {
+                    stream.next_token();
+                    return Ok(value);
+                }
+                _ => E::new(
+                    E::Cause::expected_token(self.token.clone()),
+                    stream.peek_token_span(),
+                ),
+            },
+            Err(err) if err.is_fatal() => return Err(err),
+            Err(err) => err,
+        };
+
+        // Skip from the start, so that groups opened before the failure are balanced.
+        // Only the stream is moved, keeping errors reported by nested recovery. If
+        // there is no terminator, stop at the end of the stream or at an unmatched
+        // closing delimiter, which may belong to an enclosing parser.
+        stream.jump_to(checkpoint);
+        if skip_balanced(stream, std::slice::from_ref(&self.token), &self.delimiters) {
+            stream.next_token();
+        }
+
+        // Nothing was skipped, so there is nothing to recover from.
+        if stream.stream_position() == start {
+            return Err(err);
+        }
+
+        recovered(stream, err)?;
+        Ok((self.default)())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::DefaultError;
+    use crate::prelude::*;
+    use crate::report::StreamWithErrors;
+    use crate::stream::CharStream;
+
+    type Stream<'a> = StreamWithErrors<CharStream<'a>>;
+    type Error<'a> = DefaultError<Stream<'a>>;
+
+    #[test]
+    fn terminated_skips_groups_opened_before_failure() {
+        let stmt = chain!(eat::<Stream, Error>('f'), eat('('), eat('a'), eat(')')).map_to("call");
+        let mut parser = stmt
+            .terminated(';', || "recovered")
+            .nested('(', ')')
+            .repeat()
+            .collect::<Vec<_>>();
+
+        let result = parser.parse_with_errors("f(b);f(a);");
+        assert_eq!(result.output, Some(vec!["recovered", "call"]));
+
+        let spans: Vec<_> = result
+            .errors
+            .iter()
+            .map(|error| error.cause_span())
+            .collect();
+        assert_eq!(spans, [&(2..3)]);
+    }
+
+    #[test]
+    fn terminated_keeps_errors_from_nested_recovery() {
+        let item = chain!(
+            eat::<Stream, Error>('a').or_recover(eat('b')),
+            eat(' '),
+            eat('x')
+        )
+        .map_to("item");
+        let mut parser = item.terminated(';', || "recovered");
+
+        let result = parser.parse_with_errors("b y;");
+        assert_eq!(result.output, Some("recovered"));
+
+        let spans: Vec<_> = result
+            .errors
+            .iter()
+            .map(|error| error.cause_span())
+            .collect();
+        assert_eq!(spans, [&(0..1), &(2..3)]);
+    }
+
+    #[test]
+    fn or_recover_reports_errors_while_recovering() {
+        let mut parser = eat::<Stream, Error>('a').or_recover(eat('b'));
+
+        let result = parser.parse_with_errors("c");
+        assert_eq!(result.output, None);
+
+        let errors: Vec<_> = result.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "expected `a`",
+                "while recovering from an earlier error: expected `b`"
+            ]
+        );
+        assert!(matches!(
+            &result.errors[1],
+            DefaultError::Recovering { from, .. } if *from == (0..1)
+        ));
+    }
+
+    #[test]
+    fn or_recover_retracts_on_failure() {
+        let mut parser = eat::<Stream, Error>('a')
+            .or_recover(chain!(eat('b'), eat('c')).map(|(b, _)| b))
+            .retract_on_failure();
+
+        let result = parser.parse_with_errors("bd");
+        assert_eq!(result.output, None);
+
+        let spans: Vec<_> = result
+            .errors
+            .iter()
+            .map(|error| error.cause_span())
+            .collect();
+        assert_eq!(spans, [&(1..2)]);
+
+        // A successful recovery still reports the original error.
+        let result = parser.parse_with_errors("bc");
+        assert_eq!(result.output, Some('b'));
+        assert_eq!(result.errors.len(), 1);
+    }
+}
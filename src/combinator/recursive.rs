@@ -0,0 +1,232 @@
+use std::cell::{OnceCell, RefCell};
+use std::rc::{Rc, Weak};
+
+use derive_where::derive_where;
+
+use crate::error::Error;
+use crate::parser::Parser;
+use crate::stream::Stream;
+
+/// Create a parser that can refer to itself.
+///
+/// `f` is passed a handle to the parser being defined, which can be cloned into
+/// the sub-parsers that make up its definition.
+///
+/// The definition is cloned whenever the parser is re-entered, so it must implement
+/// [`Clone`]. Clones are pooled and reused between runs.
+#[inline]
+pub fn recursive<'a, P, F, S, E>(f: F) -> Recursive<'a, S, E, P::Output>
+where
+    P: Parser<S, E> + Clone + 'a,
+    F: FnOnce(Recursive<'a, S, E, P::Output>) -> P,
+    S: Stream,
+    E: Error<S>,
+{
+    let definition = Rc::new(Definition::default());
+
+    let this = Recursive {
+        handle: Handle::Unowned(Rc::downgrade(&definition)),
+    };
+    definition.define(f(this));
+
+    Recursive {
+        handle: Handle::Owned(definition),
+    }
+}
+
+/// A handle to a parser that may be defined in terms of itself.
+///
+/// Create one with [`recursive`], or declare one with [`Recursive::declare`]
+/// and define it later with [`Recursive::define`].
+#[derive_where(Clone)]
+pub struct Recursive<'a, S, E, O> {
+    handle: Handle<'a, S, E, O>,
+}
+
+#[derive_where(Clone)]
+enum Handle<'a, S, E, O> {
+    Owned(Rc<Definition<'a, S, E, O>>),
+    Unowned(Weak<Definition<'a, S, E, O>>),
+}
+
+impl<'a, S, E, O> Recursive<'a, S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    /// Declare a parser without defining it.
+    ///
+    /// Every clone of the returned handle keeps the definition alive, so a
+    /// definition that refers to itself through these handles is never freed.
+    /// Prefer [`recursive`] where possible.
+    #[inline]
+    pub fn declare() -> Self {
+        Self {
+            handle: Handle::Owned(Rc::new(Definition::default())),
+        }
+    }
+
+    /// Define a declared parser.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parser has already been defined.
+    #[inline]
+    pub fn define<P>(&self, parser: P)
+    where
+        P: Parser<S, E, Output = O> + Clone + 'a,
+    {
+        self.definition().define(parser);
+    }
+
+    #[inline]
+    fn definition(&self) -> Rc<Definition<'a, S, E, O>> {
+        match &self.handle {
+            Handle::Owned(definition) => definition.clone(),
+            Handle::Unowned(definition) => definition
+                .upgrade()
+                .expect("recursive parser used after being dropped"),
+        }
+    }
+}
+
+impl<'a, S, E, O> Parser<S, E> for Recursive<'a, S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = O;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let definition = self.definition();
+
+        let mut parser = definition.take_instance();
+        let result = parser.parse_dyn(stream);
+        definition.idle.borrow_mut().push(parser);
+
+        result
+    }
+}
+
+struct Definition<'a, S, E, O> {
+    prototype: OnceCell<BoxedDynParser<'a, S, E, O>>,
+
+    /// Instances that are not currently being run.
+    idle: RefCell<Vec<BoxedDynParser<'a, S, E, O>>>,
+}
+
+impl<S, E, O> Default for Definition<'_, S, E, O> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            prototype: OnceCell::new(),
+            idle: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<'a, S, E, O> Definition<'a, S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    fn define<P>(&self, parser: P)
+    where
+        P: Parser<S, E, Output = O> + Clone + 'a,
+    {
+        if self.prototype.set(Box::new(parser)).is_err() {
+            panic!("recursive parser defined twice");
+        }
+    }
+
+    #[inline]
+    fn take_instance(&self) -> BoxedDynParser<'a, S, E, O> {
+        match self.idle.borrow_mut().pop() {
+            Some(parser) => parser,
+            None => self
+                .prototype
+                .get()
+                .expect("recursive parser used before being defined")
+                .clone_dyn(),
+        }
+    }
+}
+
+type BoxedDynParser<'a, S, E, O> = Box<dyn DynParser<'a, S, E, O> + 'a>;
+
+/// An object-safe parser that can be cloned into a box.
+trait DynParser<'a, S, E, O> {
+    fn parse_dyn(&mut self, stream: &mut S) -> Result<O, E>;
+    fn clone_dyn(&self) -> BoxedDynParser<'a, S, E, O>;
+}
+
+impl<'a, P, S, E> DynParser<'a, S, E, P::Output> for P
+where
+    P: Parser<S, E> + Clone + 'a,
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    fn parse_dyn(&mut self, stream: &mut S) -> Result<P::Output, E> {
+        self.parse(stream)
+    }
+
+    #[inline]
+    fn clone_dyn(&self) -> BoxedDynParser<'a, S, E, P::Output> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DefaultError;
+    use crate::prelude::*;
+    use crate::stream::CharStream;
+
+    type Error<'a> = DefaultError<CharStream<'a>>;
+
+    /// Parse nested parentheses, returning how deeply they are nested.
+    fn depth<'a>() -> Recursive<'a, CharStream<'a>, Error<'a>, usize> {
+        recursive(|depth| {
+            between(eat('('), depth.map(|n| n + 1).optional(), eat(')')).map(|n| n.unwrap_or(1))
+        })
+    }
+
+    #[test]
+    fn parses_deep_nesting() {
+        let input = format!("{}{}", "(".repeat(200), ")".repeat(200));
+
+        assert_eq!(depth().parse_complete(&input).ok(), Some(200));
+        assert!(depth().parse_complete(&input[1..]).is_err());
+    }
+
+    #[test]
+    fn reuses_instances_between_runs() {
+        let mut parser = depth();
+
+        for _ in 0..3 {
+            assert_eq!(parser.parse_complete("((()))").ok(), Some(3));
+        }
+        // One instance per level, plus the innermost attempt that found `)`.
+        assert_eq!(parser.definition().idle.borrow().len(), 4);
+    }
+
+    #[test]
+    fn declare_then_define() {
+        let list = Recursive::<CharStream, Error, usize>::declare();
+        list.define(alt!(
+            chain!(eat('a'), list.clone()).map(|(_, n)| n + 1),
+            end().map_to(0)
+        ));
+
+        assert_eq!(list.clone().parse_complete("aaaa").ok(), Some(4));
+    }
+
+    #[test]
+    #[should_panic = "recursive parser used before being defined"]
+    fn panics_when_undefined() {
+        let _ = Recursive::<CharStream, Error, ()>::declare().parse_complete("");
+    }
+}
@@ -8,75 +8,89 @@ use crate::{
     stream::Stream,
 };
 
-// #[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P)]
-// pub struct Repeat<P, Collection, S, O, E> {
-//     pub(crate) parser: P,
-//     pub(crate) min: usize,
-//     pub(crate) max: Option<usize>,
-//     pub(crate) _phantom: PhantomData<*const (Collection, S, O, E)>,
-// }
-
-// impl<P, Collection, S, O, E> Repeat<P, Collection, S, O, E>
-// where
-//     P: Parser<S, O, E>,
-//     Collection: FromIterator<O>,
-//     S: Stream,
-//     E: Error<S>,
-// {
-//     #[inline]
-//     pub fn min(mut self, min: usize) -> Self {
-//         self.min = min;
-//         self
-//     }
-
-//     #[inline]
-//     pub fn max(mut self, max: usize) -> Self {
-//         self.max = Some(max);
-//         self
-//     }
-
-//     #[inline]
-//     pub fn collect<C: FromIterator<O>>(self) -> Repeat<P, C, S, O, E> {
-//         Repeat {
-//             parser: self.parser,
-//             min: self.min,
-//             max: self.max,
-//             _phantom: PhantomData,
-//         }
-//     }
-// }
-
-// impl<P, Collection, S, O, E> Parser<S, Collection, E> for Repeat<P, Collection, S, O, E>
-// where
-//     P: Parser<S, O, E>,
-//     Collection: FromIterator<O>,
-//     S: Stream,
-//     E: Error<S>,
-// {
-//     fn parse(&mut self, stream: &mut S) -> Result<Collection, E> {
-//         let mut n = 0;
-
-//         let max = self.max.map(|m| m.max(self.min));
-
-//         std::iter::from_fn(|| {
-//             if max.is_some_and(|m| n >= m) {
-//                 None
-//             } else {
-//                 n += 1;
-
-//                 match self.parser.parse(stream) {
-//                     Ok(output) => Some(Ok(output)),
-
-//                     // Not enough repetitions.
-//                     Err(err) if n <= self.min => Some(Err(err)),
-
-//                     Err(_) => None,
-//                 }
-//             }
-//         })
-//         .collect()
-//     }
-// }
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P)]
+pub struct Repeat<P, Collection, S, E> {
+    pub(crate) parser: P,
+
+    pub(crate) min: usize,
+    pub(crate) max: Option<NonZeroUsize>,
+
+    pub(crate) _phantom: PhantomData<*const (Collection, S, E)>,
+}
+
+impl<P, Collection, S, E> Repeat<P, Collection, S, E>
+where
+    P: Parser<S, E>,
+    Collection: FromIterator<P::Output>,
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    pub fn min(mut self, min: usize) -> Self {
+        self.min = min;
+        self
+    }
+
+    #[inline]
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(NonZeroUsize::new(max).expect("`max` must be non-zero"));
+        self
+    }
+
+    #[inline]
+    pub fn collect<C: FromIterator<P::Output>>(self) -> Repeat<P, C, S, E> {
+        Repeat {
+            parser: self.parser,
+            min: self.min,
+            max: self.max,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, Collection, S, E> Parser<S, E> for Repeat<P, Collection, S, E>
+where
+    P: Parser<S, E>,
+    Collection: FromIterator<P::Output>,
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = Collection;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        debug_assert!(self.max.is_none_or(|m| m.get() >= self.min));
+
+        let mut n = 0;
+
+        std::iter::from_fn(|| {
+            if self.max.is_some_and(|max| n >= max.get()) {
+                return None;
+            }
+
+            let start = stream.stream_position();
+
+            match self.parser.parse(stream) {
+                Ok(output) => {
+                    if stream.stream_position() == start {
+                        panic!("parser did not make progress");
+                    }
+
+                    n += 1;
+                    Some(Ok(output))
+                }
+
+                // The parser failed part-way through, there weren't enough repetitions,
+                // or the error is fatal.
+                Err(err) if stream.stream_position() != start || n < self.min || err.is_fatal() => {
+                    Some(Err(err))
+                }
+
+                Err(_) => None,
+            }
+        })
+        .collect()
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct NoCollection;
@@ -165,6 +179,10 @@ where
 
                     Some(result)
                 }
+                None if stream.is_partial() => Some(Err(E::new(
+                    E::Cause::incomplete(None),
+                    stream.peek_token_span(),
+                ))),
                 _ if n < self.min => {
                     Some(Err(E::new(E::Cause::unknown(), stream.peek_token_span())))
                 }
@@ -174,3 +192,340 @@ where
         .collect()
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Trailing {
+    Forbid,
+    Allow,
+    Require,
+}
+
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P, Sep)]
+pub struct SeparatedBy<P, Sep, Collection, S, E> {
+    pub(crate) parser: P,
+    pub(crate) separator: Sep,
+
+    pub(crate) min: usize,
+    pub(crate) max: Option<NonZeroUsize>,
+    pub(crate) trailing: Trailing,
+
+    pub(crate) _phantom: PhantomData<*const (Collection, S, E)>,
+}
+
+impl<P, Sep, Collection, S, E> SeparatedBy<P, Sep, Collection, S, E>
+where
+    P: Parser<S, E>,
+    Sep: Parser<S, E>,
+    Collection: FromIterator<P::Output>,
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    pub fn min(mut self, min: usize) -> Self {
+        self.min = min;
+        self
+    }
+
+    #[inline]
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(NonZeroUsize::new(max).expect("`max` must be non-zero"));
+        self
+    }
+
+    /// Allow (but don't require) a separator after the last item.
+    #[inline]
+    pub fn allow_trailing(mut self) -> Self {
+        self.trailing = Trailing::Allow;
+        self
+    }
+
+    /// Require a separator after every item, including the last.
+    #[inline]
+    pub fn require_trailing(mut self) -> Self {
+        self.trailing = Trailing::Require;
+        self
+    }
+
+    #[inline]
+    pub fn collect<C: FromIterator<P::Output>>(self) -> SeparatedBy<P, Sep, C, S, E> {
+        SeparatedBy {
+            parser: self.parser,
+            separator: self.separator,
+            min: self.min,
+            max: self.max,
+            trailing: self.trailing,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<P, Sep, Collection, S, E> SeparatedBy<P, Sep, Collection, S, E>
+where
+    P: Parser<S, E>,
+    Sep: Parser<S, E>,
+    Collection: FromIterator<P::Output>,
+    S: Stream,
+    E: Error<S>,
+{
+    /// Parse the list, along with the error from the separator or item that failed
+    /// without consuming input and so ended it, if any.
+    fn parse_list(&mut self, stream: &mut S) -> Result<(Collection, Option<E>), E> {
+        debug_assert!(self.max.is_none_or(|m| m.get() >= self.min));
+
+        let mut n = 0;
+        let mut done = false;
+        let mut ended_by = None;
+
+        let items = std::iter::from_fn(|| {
+            if done {
+                return None;
+            }
+
+            let iteration_start = stream.stream_position();
+
+            if n > 0 {
+                let max_reached = self.max.is_some_and(|max| n >= max.get());
+                if max_reached && self.trailing == Trailing::Forbid {
+                    return None;
+                }
+
+                let start = stream.stream_position();
+                match self.separator.parse(stream) {
+                    Ok(_) => {}
+
+                    // The separator failed part-way through, a separator was required,
+                    // or the error is fatal.
+                    Err(err)
+                        if stream.stream_position() != start
+                            || n < self.min
+                            || self.trailing == Trailing::Require
+                            || err.is_fatal() =>
+                    {
+                        return Some(Err(err));
+                    }
+
+                    Err(err) => {
+                        ended_by = Some(err);
+                        return None;
+                    }
+                }
+
+                if max_reached {
+                    done = true;
+                    return None;
+                }
+            }
+
+            let start = stream.stream_position();
+            match self.parser.parse(stream) {
+                Ok(output) => {
+                    if n > 0 && stream.stream_position() == iteration_start {
+                        panic!("parser did not make progress");
+                    }
+
+                    n += 1;
+                    Some(Ok(output))
+                }
+
+                // The item failed part-way through, there weren't enough items, or the
+                // error is fatal.
+                Err(err) if stream.stream_position() != start || n < self.min || err.is_fatal() => {
+                    Some(Err(err))
+                }
+
+                // A separator was consumed but is not allowed to trail.
+                Err(err) if n > 0 && self.trailing == Trailing::Forbid => Some(Err(err)),
+
+                Err(err) => {
+                    ended_by = Some(err);
+                    None
+                }
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+        Ok((items, ended_by))
+    }
+}
+
+impl<P, Sep, Collection, S, E> Parser<S, E> for SeparatedBy<P, Sep, Collection, S, E>
+where
+    P: Parser<S, E>,
+    Sep: Parser<S, E>,
+    Collection: FromIterator<P::Output>,
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = Collection;
+
+    #[inline]
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        self.parse_list(stream).map(|(items, _)| items)
+    }
+}
+
+/// Parse a list of items separated by `separator` and enclosed by `open` and `close`.
+///
+/// By default, a trailing separator is not allowed. If `close` fails, its error is
+/// merged with the error from the separator or item that ended the list, so that
+/// both are expected at that position.
+#[inline]
+pub fn delimited_list<Open, P, Sep, Close, S, E>(
+    open: Open,
+    parser: P,
+    separator: Sep,
+    close: Close,
+) -> DelimitedList<Open, P, Sep, Close, S, E>
+where
+    Open: Parser<S, E>,
+    P: Parser<S, E>,
+    Sep: Parser<S, E>,
+    Close: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    DelimitedList {
+        open,
+        list: parser.separated_by(separator).collect(),
+        close,
+    }
+}
+
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; Open, P, Sep, Close)]
+pub struct DelimitedList<Open, P, Sep, Close, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    open: Open,
+    list: SeparatedBy<P, Sep, Vec<P::Output>, S, E>,
+    close: Close,
+}
+
+impl<Open, P, Sep, Close, S, E> DelimitedList<Open, P, Sep, Close, S, E>
+where
+    Open: Parser<S, E>,
+    P: Parser<S, E>,
+    Sep: Parser<S, E>,
+    Close: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    /// Allow (but don't require) a separator after the last item.
+    #[inline]
+    pub fn allow_trailing(mut self) -> Self {
+        self.list = self.list.allow_trailing();
+        self
+    }
+}
+
+impl<Open, P, Sep, Close, S, E> Parser<S, E> for DelimitedList<Open, P, Sep, Close, S, E>
+where
+    Open: Parser<S, E>,
+    P: Parser<S, E>,
+    Sep: Parser<S, E>,
+    Close: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = Vec<P::Output>;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let _ = self.open.parse(stream)?;
+        let (items, ended_by) = self.list.parse_list(stream)?;
+
+        match self.close.parse(stream) {
+            Ok(_) => Ok(items),
+            Err(err) => Err(match ended_by {
+                Some(ended_by) if !err.is_fatal() => err.merge(ended_by),
+                _ => err,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{DefaultCause, DefaultError, Expected};
+    use crate::prelude::*;
+    use crate::stream::{CharStream, Stream};
+
+    type Error<'a> = DefaultError<CharStream<'a>>;
+
+    fn list<'a>(stream: &mut CharStream<'a>) -> Result<Vec<char>, Error<'a>> {
+        delimited_list(eat('['), eat_if(char::is_ascii_digit), eat(','), eat(']')).parse(stream)
+    }
+
+    #[test]
+    fn delimited_list_expects_separator_or_close() {
+        let err = list.parse_complete("[1,2 3]").unwrap_err();
+
+        assert_eq!(*err.span(), 4..5);
+        assert_eq!(
+            *err.cause(),
+            DefaultCause::ExpectedOneOf(vec![Expected::Token(']'), Expected::Token(',')])
+        );
+    }
+
+    #[test]
+    fn delimited_list_forbids_trailing_separator_by_default() {
+        assert_eq!(list.parse_complete("[1,2]").ok(), Some(vec!['1', '2']));
+        assert!(list.parse_complete("[1,2,]").is_err());
+
+        let mut trailing = delimited_list(
+            eat::<CharStream, Error>('['),
+            eat_if(char::is_ascii_digit),
+            eat(','),
+            eat(']'),
+        )
+        .allow_trailing();
+        assert_eq!(trailing.parse_complete("[1,2,]").ok(), Some(vec!['1', '2']));
+    }
+
+    fn digits<'a>() -> impl Parser<CharStream<'a>, Error<'a>, Output = char> {
+        eat_if(char::is_ascii_digit)
+    }
+
+    #[test]
+    fn repeat_requires_min() {
+        let mut parser = digits().repeat().min(2).collect::<String>();
+
+        assert_eq!(parser.parse_complete("12").ok(), Some("12".into()));
+        assert_eq!(parser.parse_complete("123").ok(), Some("123".into()));
+
+        let err = parser.parse_complete("1a").unwrap_err();
+        assert_eq!(*err.span(), 1..2);
+    }
+
+    #[test]
+    fn repeat_stops_at_max() {
+        let mut parser = digits().repeat().max(2).collect::<String>();
+
+        assert_eq!(parser.parse_complete("").ok(), Some("".into()));
+        assert_eq!(parser.parse_complete("12").ok(), Some("12".into()));
+
+        let mut stream = CharStream::new("123");
+        assert_eq!(parser.parse(&mut stream).ok(), Some("12".into()));
+        assert_eq!(stream.peek_token(), Some('3'));
+    }
+
+    #[test]
+    fn repeat_fails_part_way_through_an_item() {
+        let mut parser = chain!(digits(), eat(';')).repeat().collect::<Vec<_>>();
+
+        assert_eq!(parser.parse_complete("1;2;").ok().map(|v| v.len()), Some(2));
+
+        let err = parser.parse_complete("1;2").unwrap_err();
+        assert_eq!(*err.span(), 3..3);
+        assert_eq!(*err.cause(), DefaultCause::ExpectedToken(';'));
+    }
+
+    #[test]
+    #[should_panic = "parser did not make progress"]
+    fn repeat_panics_without_progress() {
+        let _ = eat_while::<_, CharStream, Error>(char::is_ascii_digit)
+            .repeat()
+            .parse_complete("a");
+    }
+}
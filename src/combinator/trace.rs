@@ -0,0 +1,40 @@
+use std::marker::PhantomData;
+
+use derive_where::derive_where;
+
+use crate::error::Error;
+use crate::parser::Parser;
+use crate::stream::Stream;
+
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; P)]
+pub struct Trace<P, S, E> {
+    pub(crate) parser: P,
+    #[cfg_attr(not(feature = "trace"), allow(dead_code))]
+    pub(crate) name: &'static str,
+    pub(crate) _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, S, E> Parser<S, E> for Trace<P, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    type Output = P::Output;
+
+    #[cfg(feature = "trace")]
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        let guard = crate::trace::enter(self.name, stream.stream_position());
+
+        let result = self.parser.parse(stream);
+        guard.exit(stream.stream_position(), result.is_ok());
+
+        result
+    }
+
+    #[cfg(not(feature = "trace"))]
+    #[inline]
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        self.parser.parse(stream)
+    }
+}
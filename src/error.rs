@@ -1,9 +1,30 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::num::NonZeroUsize;
+
 use derive_where::derive_where;
 
-use crate::stream::Stream;
+use crate::stream::{Span, Stream};
 
 pub trait Report<Error> {
     fn report(&mut self, error: Error);
+
+    /// Whether so many errors have been reported that parsing should stop.
+    ///
+    /// Once this is true, parsers that recover from errors fail with a
+    /// [too many errors](Cause::too_many_errors) error instead.
+    #[inline]
+    fn limit_reached(&self) -> bool {
+        false
+    }
+}
+
+/// An error that knows where it occurred, so that it can be ordered among other
+/// errors, as in an [`ErrorCollector`](crate::report::ErrorCollector).
+pub trait ErrorSpan {
+    type Span: Span;
+
+    fn error_span(&self) -> &Self::Span;
 }
 
 pub trait Error<S: Stream> {
@@ -11,6 +32,51 @@ pub trait Error<S: Stream> {
 
     fn new(cause: Self::Cause, span: S::Span) -> Self;
     fn set_cause(&mut self, cause: Self::Cause);
+
+    /// Combine this error with an error from another alternative that failed at the
+    /// same position.
+    ///
+    /// By default, this error is kept and the other is discarded.
+    /// [`DefaultError`] keeps whichever error is furthest into the stream, and merges
+    /// the causes of errors at the same position.
+    #[inline]
+    fn merge(self, other: Self) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = other;
+        self
+    }
+
+    /// Mark this error as having occurred while recovering from an earlier error at
+    /// `span`.
+    ///
+    /// By default, the error is unchanged.
+    #[inline]
+    fn recovering_from(self, span: S::Span) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = span;
+        self
+    }
+
+    /// Whether the error was caused by reaching the end of a
+    /// [partial](Stream::is_partial) stream, so that the parse may succeed once
+    /// more input is available.
+    #[inline]
+    fn is_incomplete(&self) -> bool {
+        false
+    }
+
+    /// Whether the error should be returned immediately, rather than backtracked
+    /// past or recovered from.
+    ///
+    /// By default, only [incomplete](Error::is_incomplete) errors are fatal.
+    #[inline]
+    fn is_fatal(&self) -> bool {
+        self.is_incomplete()
+    }
 }
 
 pub trait ErrorWithContext<S: Stream>: Error<S> {
@@ -27,6 +93,56 @@ pub trait Cause<S: Stream> {
     fn expected_end() -> Self;
 
     fn unknown() -> Self;
+
+    /// The end of a [partial](Stream::is_partial) stream was reached.
+    ///
+    /// `needed` is the number of tokens known to be missing, if any.
+    /// By default, this is an [unknown](Cause::unknown) cause.
+    #[inline]
+    fn incomplete(needed: Option<NonZeroUsize>) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = needed;
+        Self::unknown()
+    }
+
+    #[inline]
+    fn is_incomplete(&self) -> bool {
+        false
+    }
+
+    /// Too many errors have been [reported](Report::report), and parsing was
+    /// stopped.
+    ///
+    /// By default, this is an [unknown](Cause::unknown) cause.
+    #[inline]
+    fn too_many_errors() -> Self
+    where
+        Self: Sized,
+    {
+        Self::unknown()
+    }
+
+    /// Whether an error with this cause is [fatal](Error::is_fatal).
+    ///
+    /// By default, only [incomplete](Cause::is_incomplete) causes are fatal.
+    #[inline]
+    fn is_fatal(&self) -> bool {
+        self.is_incomplete()
+    }
+
+    /// Combine this cause with the cause of another error at the same position.
+    ///
+    /// By default, this cause is kept and the other is discarded.
+    #[inline]
+    fn merge(self, other: Self) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = other;
+        self
+    }
 }
 
 #[derive_where(Debug, Clone, PartialEq, Eq, Hash; S::Token, &'static S::Slice)]
@@ -36,17 +152,95 @@ pub enum DefaultCause<S: Stream> {
     ExpectedToken(S::Token),
     ExpectedSlice(&'static S::Slice),
 
+    ExpectedLabel(Box<str>),
+    ExpectedOneOf(Vec<Expected<S>>),
+
     ExpectedPredicate,
     ExpectedEnd,
 
+    /// More input is needed, and at least this many tokens if known.
+    Incomplete(Option<NonZeroUsize>),
+
+    /// Parsing was stopped because too many errors were reported.
+    TooManyErrors,
+
     Unknown,
 }
 
+/// Something that was expected, as part of [`DefaultCause::ExpectedOneOf`].
+#[derive_where(Debug, Clone, PartialEq, Eq, Hash; S::Token, &'static S::Slice)]
+pub enum Expected<S: Stream> {
+    Token(S::Token),
+    Slice(&'static S::Slice),
+    Label(Box<str>),
+    End,
+}
+
+impl<S: Stream> Expected<S> {
+    /// Whether two expectations are the same, comparing slices with
+    /// [`Stream::slices_eq`].
+    #[inline]
+    fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Token(a), Self::Token(b)) => a == b,
+            (Self::Slice(a), Self::Slice(b)) => S::slices_eq(a, b),
+            (Self::Label(a), Self::Label(b)) => a == b,
+            (Self::End, Self::End) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<S> Display for Expected<S>
+where
+    S: Stream<Token: Display, Slice: Display>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Token(token) => write!(f, "`{token}`"),
+            Self::Slice(slice) => write!(f, "`{slice}`"),
+            Self::Label(label) => write!(f, "{label}"),
+            Self::End => write!(f, "end of input"),
+        }
+    }
+}
+
 impl<S: Stream> DefaultCause<S> {
     #[inline]
     pub fn custom(custom: impl Into<Box<str>>) -> Self {
         Self::Custom(custom.into())
     }
+
+    /// Expected something described by a label, such as "digit" or "expression".
+    #[inline]
+    pub fn label(label: impl Into<Box<str>>) -> Self {
+        Self::ExpectedLabel(label.into())
+    }
+
+    #[inline]
+    fn into_expected(self) -> Result<Vec<Expected<S>>, Self> {
+        match self {
+            Self::ExpectedToken(token) => Ok(vec![Expected::Token(token)]),
+            Self::ExpectedSlice(slice) => Ok(vec![Expected::Slice(slice)]),
+            Self::ExpectedLabel(label) => Ok(vec![Expected::Label(label)]),
+            Self::ExpectedOneOf(expected) => Ok(expected),
+            Self::ExpectedEnd => Ok(vec![Expected::End]),
+            cause => Err(cause),
+        }
+    }
+
+    #[inline]
+    fn from_expected(mut expected: Vec<Expected<S>>) -> Self {
+        match expected.len() {
+            1 => match expected.pop().unwrap() {
+                Expected::Token(token) => Self::ExpectedToken(token),
+                Expected::Slice(slice) => Self::ExpectedSlice(slice),
+                Expected::Label(label) => Self::ExpectedLabel(label),
+                Expected::End => Self::ExpectedEnd,
+            },
+            _ => Self::ExpectedOneOf(expected),
+        }
+    }
 }
 
 impl<S: Stream> Cause<S> for DefaultCause<S> {
@@ -74,6 +268,92 @@ impl<S: Stream> Cause<S> for DefaultCause<S> {
     fn unknown() -> Self {
         Self::Unknown
     }
+
+    #[inline]
+    fn incomplete(needed: Option<NonZeroUsize>) -> Self {
+        Self::Incomplete(needed)
+    }
+
+    #[inline]
+    fn is_incomplete(&self) -> bool {
+        matches!(self, Self::Incomplete(_))
+    }
+
+    #[inline]
+    fn too_many_errors() -> Self {
+        Self::TooManyErrors
+    }
+
+    #[inline]
+    fn is_fatal(&self) -> bool {
+        matches!(self, Self::Incomplete(_) | Self::TooManyErrors)
+    }
+
+    /// Merge the expectations of both causes, leaving out repeated ones.
+    ///
+    /// If either cause isn't an expectation, the first cause is kept, unless it is
+    /// [`Unknown`](DefaultCause::Unknown). A [fatal](Cause::is_fatal) cause is always
+    /// kept.
+    fn merge(self, other: Self) -> Self {
+        if other.is_fatal() && !self.is_fatal() {
+            return other;
+        }
+
+        match (self.into_expected(), other.into_expected()) {
+            (Ok(mut expected), Ok(other)) => {
+                for item in other {
+                    if !expected.iter().any(|e| e.same_as(&item)) {
+                        expected.push(item);
+                    }
+                }
+                Self::from_expected(expected)
+            }
+
+            (Err(Self::Unknown), Ok(other)) => Self::from_expected(other),
+            (Err(Self::Unknown), Err(other)) => other,
+
+            (Ok(expected), Err(_)) => Self::from_expected(expected),
+            (Err(cause), _) => cause,
+        }
+    }
+}
+
+impl<S> Display for DefaultCause<S>
+where
+    S: Stream<Token: Display, Slice: Display>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Custom(custom) => write!(f, "{custom}"),
+
+            Self::ExpectedToken(token) => write!(f, "expected `{token}`"),
+            Self::ExpectedSlice(slice) => write!(f, "expected `{slice}`"),
+
+            Self::ExpectedLabel(label) => write!(f, "expected {label}"),
+            Self::ExpectedOneOf(expected) => {
+                write!(f, "expected one of ")?;
+                for (i, item) in expected.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                Ok(())
+            }
+
+            Self::ExpectedPredicate => write!(f, "unexpected token"),
+            Self::ExpectedEnd => write!(f, "expected end of input"),
+
+            Self::Incomplete(Some(needed)) => {
+                write!(f, "unexpected end of input (needed {needed} more)")
+            }
+            Self::Incomplete(None) => write!(f, "unexpected end of input"),
+
+            Self::TooManyErrors => write!(f, "too many errors"),
+
+            Self::Unknown => write!(f, "unknown error"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -88,6 +368,13 @@ pub enum DefaultError<S: Stream, C: Cause<S> = DefaultCause<S>, Context = Box<st
         span: S::Span,
         inner: Box<Self>,
     },
+
+    /// An error from a recovery parser, which was recovering from an earlier error
+    /// at `from`.
+    Recovering {
+        from: S::Span,
+        inner: Box<Self>,
+    },
 }
 
 impl<S, C, Context> DefaultError<S, C, Context>
@@ -100,6 +387,45 @@ where
         match self {
             Self::Error { span, cause: _ } => span,
             Self::WithContext { span, .. } => span,
+            Self::Recovering { inner, .. } => inner.span(),
+        }
+    }
+
+    /// The cause of the error, ignoring any context.
+    #[inline]
+    pub fn cause(&self) -> &C {
+        match self {
+            Self::Error { cause, .. } => cause,
+            Self::WithContext { inner, .. } => inner.cause(),
+            Self::Recovering { inner, .. } => inner.cause(),
+        }
+    }
+
+    /// The span of the error, ignoring any context.
+    #[inline]
+    pub fn cause_span(&self) -> &S::Span {
+        match self {
+            Self::Error { span, .. } => span,
+            Self::WithContext { inner, .. } => inner.cause_span(),
+            Self::Recovering { inner, .. } => inner.cause_span(),
+        }
+    }
+
+    #[inline]
+    fn into_cause(self) -> C {
+        match self {
+            Self::Error { cause, .. } => cause,
+            Self::WithContext { inner, .. } => inner.into_cause(),
+            Self::Recovering { inner, .. } => inner.into_cause(),
+        }
+    }
+
+    #[inline]
+    fn cause_mut(&mut self) -> &mut C {
+        match self {
+            Self::Error { cause, .. } => cause,
+            Self::WithContext { inner, .. } => inner.cause_mut(),
+            Self::Recovering { inner, .. } => inner.cause_mut(),
         }
     }
 }
@@ -124,8 +450,42 @@ where
             } => *prev_cause = cause,
 
             Self::WithContext { inner, .. } => inner.set_cause(cause),
+            Self::Recovering { inner, .. } => inner.set_cause(cause),
+        }
+    }
+
+    /// Keep the error that is furthest into the stream, merging the causes of
+    /// errors at the same position (ignoring context).
+    fn merge(mut self, other: Self) -> Self {
+        match self.cause_span().cmp_start(other.cause_span()) {
+            Ordering::Greater => self,
+            Ordering::Less => other,
+            Ordering::Equal => {
+                let cause = self.cause_mut();
+                let merged = std::mem::replace(cause, C::unknown()).merge(other.into_cause());
+                *cause = merged;
+                self
+            }
+        }
+    }
+
+    #[inline]
+    fn recovering_from(self, span: S::Span) -> Self {
+        Self::Recovering {
+            from: span,
+            inner: Box::new(self),
         }
     }
+
+    #[inline]
+    fn is_incomplete(&self) -> bool {
+        self.cause().is_incomplete()
+    }
+
+    #[inline]
+    fn is_fatal(&self) -> bool {
+        self.cause().is_fatal()
+    }
 }
 
 impl<S, C, Context> ErrorWithContext<S> for DefaultError<S, C, Context>
@@ -144,3 +504,97 @@ where
         }
     }
 }
+
+/// The span of the [cause](DefaultError::cause_span).
+impl<S, C, Context> ErrorSpan for DefaultError<S, C, Context>
+where
+    S: Stream,
+    C: Cause<S>,
+{
+    type Span = S::Span;
+
+    #[inline]
+    fn error_span(&self) -> &Self::Span {
+        self.cause_span()
+    }
+}
+
+impl<S, C, Context> Display for DefaultError<S, C, Context>
+where
+    S: Stream,
+    C: Cause<S> + Display,
+    Context: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error { cause, .. } => write!(f, "{cause}"),
+            Self::WithContext { context, inner, .. } => write!(f, "{context}: {inner}"),
+            Self::Recovering { inner, .. } => {
+                write!(f, "while recovering from an earlier error: {inner}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::*;
+    use crate::stream::{AsToken, CharStream, SliceStream};
+
+    #[test]
+    fn merge_deduplicates_equal_slices() {
+        // Equal slices at different addresses.
+        let a: &'static str = Box::leak(String::from("let").into_boxed_str());
+        let b: &'static str = Box::leak(String::from("let").into_boxed_str());
+        assert!(!std::ptr::eq(a, b));
+
+        let merged = DefaultCause::<CharStream>::expected_slice(a)
+            .merge(DefaultCause::expected_slice(b))
+            .merge(DefaultCause::expected_token('x'));
+
+        assert_eq!(
+            merged,
+            DefaultCause::ExpectedOneOf(vec![Expected::Slice("let"), Expected::Token('x')])
+        );
+    }
+
+    /// A token that can't be compared, although its kind can.
+    #[derive(Debug)]
+    struct Tok(char, Range<usize>);
+
+    impl AsToken for Tok {
+        type Token = char;
+        type Span = Range<usize>;
+
+        fn as_token(&self) -> Self::Token {
+            self.0
+        }
+
+        fn as_span(&self) -> Self::Span {
+            self.1.clone()
+        }
+    }
+
+    #[test]
+    fn merge_with_incomparable_slices() {
+        type Error = DefaultError<SliceStream<'static, Tok>>;
+
+        let slice: &'static [Tok] = Box::leak(Box::new([Tok('a', 0..1)]));
+
+        let merged = Error::new(DefaultCause::expected_slice(slice), 0..1)
+            .merge(Error::new(DefaultCause::expected_slice(slice), 0..1))
+            .merge(Error::new(DefaultCause::expected_token('x'), 0..1))
+            .merge(Error::new(DefaultCause::expected_token('x'), 0..1));
+
+        match merged.cause() {
+            DefaultCause::ExpectedOneOf(expected) => {
+                assert!(
+                    matches!(expected[..], [Expected::Slice(s), Expected::Token('x')] if std::ptr::eq(s, slice))
+                );
+            }
+            cause => panic!("unexpected cause: {cause:?}"),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Lexing source text into tokens that can be parsed with a [`SliceStream`].
+//!
+//! Each kind of token is lexed by an ordinary parser over a [`CharStream`]. At each
+//! position, skip rules (for whitespace and comments) are run until none match,
+//! then the token rules are tried and the longest match wins. Ties go to the rule
+//! that was added first.
+//!
+//! [`SliceStream`]: crate::stream::SliceStream
+
+use std::ops::Range;
+
+use crate::combinator::{furthest_error, BoxedParser};
+use crate::error::{Cause, DefaultError, Error};
+use crate::parser::Parser;
+use crate::stream::{AsToken, CharStream, Stream};
+
+/// A token kind, along with the span of source text it was lexed from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spanned<K>(pub K, pub Range<usize>);
+
+impl<K: PartialEq + Clone + 'static> AsToken for Spanned<K> {
+    type Token = K;
+    type Span = Range<usize>;
+
+    #[inline]
+    fn as_token(&self) -> Self::Token {
+        self.0.clone()
+    }
+
+    #[inline]
+    fn as_span(&self) -> Self::Span {
+        self.1.clone()
+    }
+}
+
+/// A lexer made up of token rules and skip rules.
+///
+/// The lexer is itself a parser, which lexes the rest of the stream into a
+/// [`Vec`] of [`Spanned`] tokens.
+pub struct Lexer<'a, K, E = DefaultError<CharStream<'a>>> {
+    rules: Vec<BoxedParser<'a, CharStream<'a>, E, K>>,
+    skips: Vec<BoxedParser<'a, CharStream<'a>, E, ()>>,
+
+    /// Whether the first matching rule wins, rather than the longest match.
+    priority: bool,
+    error_token: Option<K>,
+}
+
+impl<'a, K, E> Default for Lexer<'a, K, E>
+where
+    K: Clone,
+    E: Error<CharStream<'a>>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, K, E> Lexer<'a, K, E>
+where
+    K: Clone,
+    E: Error<CharStream<'a>>,
+{
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            rules: vec![],
+            skips: vec![],
+            priority: false,
+            error_token: None,
+        }
+    }
+
+    /// Add a rule that lexes a token of the kind it outputs.
+    ///
+    /// Rules that succeed without consuming any input are treated as not matching.
+    #[inline]
+    pub fn token<P>(mut self, parser: P) -> Self
+    where
+        P: Parser<CharStream<'a>, E, Output = K> + 'a,
+    {
+        self.rules.push(BoxedParser::new(parser));
+        self
+    }
+
+    /// Add a rule for input that separates tokens, such as whitespace or comments.
+    #[inline]
+    pub fn skip<P>(mut self, parser: P) -> Self
+    where
+        P: Parser<CharStream<'a>, E> + 'a,
+        E: 'a,
+    {
+        self.skips.push(BoxedParser::new(parser.map_to(())));
+        self
+    }
+
+    /// Choose between rules that match at the same position by the order they were
+    /// added, rather than by the longest match.
+    #[inline]
+    pub fn priority(mut self) -> Self {
+        self.priority = true;
+        self
+    }
+
+    /// Emit a token of this kind for input that no rule matches, rather than
+    /// failing.
+    ///
+    /// Consecutive unmatched characters are combined into a single token.
+    #[inline]
+    pub fn error_token(mut self, kind: K) -> Self {
+        self.error_token = Some(kind);
+        self
+    }
+
+    /// Lex a string into tokens.
+    #[inline]
+    pub fn lex(&mut self, source: &'a str) -> Result<Vec<Spanned<K>>, E> {
+        self.parse(&mut CharStream::new(source))
+    }
+
+    fn skip_all(&mut self, stream: &mut CharStream<'a>) {
+        'outer: loop {
+            for skip in &mut self.skips {
+                let checkpoint = stream.checkpoint();
+                let start = stream.stream_position();
+
+                match skip.parse(stream) {
+                    Ok(()) if stream.stream_position() > start => continue 'outer,
+                    _ => stream.rewind(checkpoint),
+                }
+            }
+
+            break;
+        }
+    }
+
+    /// Run the token rules, leaving the stream after the winning match.
+    fn lex_token(&mut self, stream: &mut CharStream<'a>) -> Result<K, E> {
+        let start = stream.checkpoint();
+        let start_position = stream.stream_position();
+
+        let mut best: Option<(K, usize, <CharStream<'a> as Stream>::Checkpoint)> = None;
+        let mut best_error = None;
+
+        for rule in &mut self.rules {
+            match rule.parse(stream) {
+                Ok(kind) if stream.stream_position() > start_position => {
+                    let end = stream.stream_position();
+                    if best.as_ref().is_none_or(|(_, best_end, _)| end > *best_end) {
+                        best = Some((kind, end, stream.checkpoint()));
+                    }
+
+                    if self.priority {
+                        break;
+                    }
+                }
+
+                Ok(_) => {}
+
+                Err(err) => {
+                    let position = stream.stream_position();
+                    best_error = furthest_error::<CharStream<'a>, E>(best_error, position, err);
+                }
+            }
+
+            stream.rewind(start.clone());
+        }
+
+        match best {
+            Some((kind, _, end)) => {
+                stream.rewind(end);
+                Ok(kind)
+            }
+
+            None => Err(match best_error {
+                Some((_, err)) => err,
+                None => E::new(E::Cause::unknown(), stream.peek_token_span()),
+            }),
+        }
+    }
+}
+
+impl<'a, K, E> Parser<CharStream<'a>, E> for Lexer<'a, K, E>
+where
+    K: Clone,
+    E: Error<CharStream<'a>>,
+{
+    type Output = Vec<Spanned<K>>;
+
+    fn parse(&mut self, stream: &mut CharStream<'a>) -> Result<Self::Output, E> {
+        let mut tokens = vec![];
+
+        // Whether the last token is an error token that can be extended.
+        let mut in_error = false;
+
+        loop {
+            self.skip_all(stream);
+            if stream.at_end() {
+                break;
+            }
+
+            let start = stream.stream_position();
+            match self.lex_token(stream) {
+                Ok(kind) => {
+                    tokens.push(Spanned(kind, start..stream.stream_position()));
+                    in_error = false;
+                }
+
+                Err(err) => {
+                    let Some(error_token) = &self.error_token else {
+                        return Err(err);
+                    };
+
+                    stream.next_token();
+                    let end = stream.stream_position();
+
+                    match tokens.last_mut() {
+                        Some(Spanned(_, span)) if in_error && span.end == start => span.end = end,
+                        _ => tokens.push(Spanned(error_token.clone(), start..end)),
+                    }
+                    in_error = true;
+                }
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        Let,
+        Ident,
+        Error,
+    }
+
+    fn lexer<'a>() -> Lexer<'a, Kind> {
+        Lexer::new()
+            .skip(eat(' '))
+            .token(eat_slice("let").map_to(Kind::Let))
+            .token(
+                eat_if(char::is_ascii_alphabetic)
+                    .drop_then(eat_while(char::is_ascii_alphabetic))
+                    .map_to(Kind::Ident),
+            )
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        assert_eq!(
+            lexer().lex("let letter").ok(),
+            Some(vec![Spanned(Kind::Let, 0..3), Spanned(Kind::Ident, 4..10)])
+        );
+    }
+
+    #[test]
+    fn priority_takes_the_first_match() {
+        assert_eq!(
+            lexer().priority().lex("letter").ok(),
+            Some(vec![Spanned(Kind::Let, 0..3), Spanned(Kind::Ident, 3..6)])
+        );
+    }
+
+    #[test]
+    fn error_tokens_cover_unmatched_input() {
+        assert_eq!(
+            lexer().error_token(Kind::Error).lex("a#$ % b").ok(),
+            Some(vec![
+                Spanned(Kind::Ident, 0..1),
+                Spanned(Kind::Error, 1..3),
+                Spanned(Kind::Error, 4..5),
+                Spanned(Kind::Ident, 6..7),
+            ])
+        );
+
+        let err = lexer().lex("a #").unwrap_err();
+        assert_eq!(*err.span(), 2..3);
+    }
+}
@@ -3,8 +3,16 @@ pub mod parser;
 pub mod stream;
 
 pub mod combinator;
+pub mod lexer;
+pub mod memo;
+pub mod pratt;
+pub mod recovery;
+pub mod report;
 pub mod token;
 
+#[cfg(feature = "trace")]
+pub mod trace;
+
 mod sealed {
     pub trait Sealed {}
 }
@@ -17,6 +25,8 @@ pub mod prelude {
     pub use crate::token::{eat_if, peek_if};
     pub use crate::token::{eat_while, seek};
 
-    pub use crate::combinator::{between, prefixed, suffixed};
-    pub use crate::{chain, select};
+    pub use crate::combinator::{between, delimited_list, prefixed, suffixed};
+    pub use crate::combinator::{left_recursive, recursive};
+    pub use crate::combinator::{lookahead, not};
+    pub use crate::{alt, chain, select};
 }
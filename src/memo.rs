@@ -0,0 +1,87 @@
+//! State for [memoized](crate::parser::Parser::memoize) parsers.
+//!
+//! Each memoized parser caches its results by stream position. The cache is kept
+//! in the parser rather than in the stream, since results often borrow from the
+//! input. Instead, the stream's state holds a [`MemoSession`] that identifies the
+//! parse, so that results from one parse are never replayed in another.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::stream::Rewind;
+
+/// Identifies a parse for memoization.
+///
+/// Every session is distinct, so create a new one for each parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoSession(u64);
+
+impl MemoSession {
+    #[inline]
+    pub fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for MemoSession {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Memoized results stay valid when the stream is rewound, so this does nothing.
+impl Rewind for MemoSession {
+    type Checkpoint = ();
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {}
+
+    #[inline]
+    fn rewind(&mut self, _checkpoint: Self::Checkpoint) {}
+}
+
+/// State that identifies the current parse for memoization.
+pub trait MemoState {
+    fn memo_session(&self) -> MemoSession;
+}
+
+impl MemoState for MemoSession {
+    #[inline]
+    fn memo_session(&self) -> MemoSession {
+        *self
+    }
+}
+
+/// The results of a memoized parser, and where the stream was left after each.
+pub(crate) struct MemoCache<O, E, Checkpoint> {
+    session: Option<MemoSession>,
+    entries: HashMap<usize, (Result<O, E>, Checkpoint)>,
+}
+
+impl<O, E, Checkpoint> Default for MemoCache<O, E, Checkpoint> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            session: None,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<O, E, Checkpoint> MemoCache<O, E, Checkpoint> {
+    /// Get the entries for a session, clearing any from a previous one.
+    #[inline]
+    pub(crate) fn entries(
+        &mut self,
+        session: MemoSession,
+    ) -> &mut HashMap<usize, (Result<O, E>, Checkpoint)> {
+        if self.session != Some(session) {
+            self.session = Some(session);
+            self.entries.clear();
+        }
+
+        &mut self.entries
+    }
+}
@@ -3,8 +3,11 @@ use std::marker::PhantomData;
 use crate::{
     combinator::*,
     error::{Error, ErrorWithContext, Report},
-    prelude::{prefixed, suffixed},
-    stream::{BorrowState, Stream},
+    memo::MemoState,
+    prelude::{end, prefixed, suffixed},
+    recovery::Strategy,
+    report::ErrorCollector,
+    stream::{BorrowState, FromInput, Rewind, Stream},
 };
 
 #[diagnostic::on_unimplemented(
@@ -22,6 +25,45 @@ where
     /// Run the parser on a stream.
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E>;
 
+    /// Run the parser on a stream created from `input`, failing if any input is
+    /// left over.
+    #[inline]
+    fn parse_complete<I>(&mut self, input: I) -> Result<Self::Output, E>
+    where
+        Self: Sized,
+        S: FromInput<I>,
+    {
+        let mut stream = S::from_input(input);
+        let output = self.parse(&mut stream)?;
+        end().parse(&mut stream)?;
+        Ok(output)
+    }
+
+    /// Run the parser on a stream created from `input`, failing if any input is
+    /// left over, and return the output along with every error that was reported.
+    ///
+    /// The stream's state is created with [`Default`], and reported errors are taken
+    /// from its [`ErrorCollector`]. If parsing fails, there is no output and the
+    /// error that it failed with comes last.
+    fn parse_with_errors<I>(&mut self, input: I) -> ParseResult<Self::Output, E>
+    where
+        Self: Sized,
+        S: FromInput<I> + BorrowState<State: AsMut<ErrorCollector<E>>>,
+    {
+        parse_collecting_errors(self, S::from_input(input))
+    }
+
+    /// Like [`parse_with_errors`](Parser::parse_with_errors), but starting with the
+    /// given state rather than the default one, for example to
+    /// [limit](ErrorCollector::with_limit) how many errors are collected.
+    fn parse_with_errors_in<I>(&mut self, input: I, state: S::State) -> ParseResult<Self::Output, E>
+    where
+        Self: Sized,
+        S: BorrowState<Inner: FromInput<I>, State: AsMut<ErrorCollector<E>>>,
+    {
+        parse_collecting_errors(self, S::from_parts(S::Inner::from_input(input), state))
+    }
+
     /// Map the output of this parser to another value.
     #[inline]
     fn map<F, O>(self, f: F) -> Map<Self, O, F, S, E>
@@ -247,18 +289,40 @@ where
         suffixed(self, parser)
     }
 
-    // #[inline]
-    // fn repeat(self) -> Repeat<Self, NoCollection, S, O, E>
-    // where
-    //     Self: Sized,
-    // {
-    //     Repeat {
-    //         parser: self,
-    //         min: 0,
-    //         max: None,
-    //         _phantom: PhantomData,
-    //     }
-    // }
+    /// Try this parser, outputting [`None`] if it fails without consuming any input.
+    ///
+    /// If the parser fails after consuming input, the error is returned.
+    #[inline]
+    fn optional(self) -> Optional<Self, S, E>
+    where
+        Self: Sized,
+    {
+        Optional {
+            parser: self,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Repeat this parser until it fails without consuming any input.
+    ///
+    /// If the parser fails after consuming input, or fails before it has
+    /// been repeated `min` times, the error is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an iteration succeeds without making progress.
+    #[inline]
+    fn repeat(self) -> Repeat<Self, NoCollection, S, E>
+    where
+        Self: Sized,
+    {
+        Repeat {
+            parser: self,
+            min: 0,
+            max: None,
+            _phantom: PhantomData,
+        }
+    }
 
     /// Repeat this parser while the next token matches the predicate.
     ///
@@ -282,38 +346,130 @@ where
         }
     }
 
+    /// Repeat this parser, separated by another parser.
+    ///
+    /// Parsing stops when the separator fails without consuming any input.
+    /// By default, a trailing separator is not allowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an iteration succeeds without making progress.
+    #[inline]
+    fn separated_by<Sep>(self, separator: Sep) -> SeparatedBy<Self, Sep, NoCollection, S, E>
+    where
+        Self: Sized,
+        Sep: Parser<S, E>,
+    {
+        SeparatedBy {
+            parser: self,
+            separator,
+            min: 0,
+            max: None,
+            trailing: Trailing::Forbid,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Cache the results of this parser by stream position, so that it runs at most
+    /// once at each position in a parse.
+    ///
+    /// A cached result is replayed by rewinding the stream to where the parser
+    /// originally finished. Since rewinding can't redo changes to the stream's state,
+    /// only results that left the state unchanged are cached, and only while the
+    /// state is as it was then. Otherwise the parser runs again, so that changes
+    /// such as reported errors aren't lost. The state must also provide a
+    /// [`MemoSession`](crate::memo::MemoSession) that identifies the parse.
+    ///
+    /// Clones of the returned parser share its cache.
+    #[inline]
+    fn memoize(self) -> Memoize<Self, S, E>
+    where
+        Self: Sized,
+        S: BorrowState<State: MemoState + Rewind<Checkpoint: PartialEq>>,
+    {
+        Memoize {
+            parser: self,
+            cache: Default::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Record when this parser starts and finishes, for debugging.
+    ///
+    /// Events are sent to the current trace sink (see the `trace` module), which prints
+    /// a tree to stderr by default. Without the `trace` feature, this does nothing.
+    #[inline]
+    fn trace(self, name: &'static str) -> Trace<Self, S, E>
+    where
+        Self: Sized,
+    {
+        Trace {
+            parser: self,
+            name,
+            _phantom: PhantomData,
+        }
+    }
+
     /// If this parser fails, report the error and then recover by running another parser.
+    ///
+    /// If recovery fails too, its error is marked as having occurred while recovering
+    /// from the original error.
     #[inline]
     fn or_recover<R>(self, recover: R) -> OrRecover<Self, R, S, E>
     where
         Self: Sized,
         R: Parser<S, E, Output = Self::Output>,
-        S: BorrowState<State: Report<E>>,
+        S: BorrowState<State: Report<E> + Rewind>,
     {
         OrRecover {
             parser: self,
             recover,
+            retract: false,
             _phantom: PhantomData,
         }
     }
 
-    // /// Create a parser terminated by a token.
-    // /// If the parser fails, reports the error and seeks until the token is found
-    // /// or the stream ends.
-    // #[inline]
-    // fn terminated<D>(self, token: S::Token, default: D) -> Terminated<Self, D, S, E>
-    // where
-    //     Self: Sized,
-    //     D: FnMut() -> Self::Output,
-    //     S: BorrowState<State: Report<E>>,
-    // {
-    //     Terminated {
-    //         parser: self,
-    //         token,
-    //         default,
-    //         _phantom: PhantomData,
-    //     }
-    // }
+    /// If this parser fails, try to recover using a [strategy](crate::recovery).
+    ///
+    /// If the strategy recovers, the error is reported. Otherwise, it is returned.
+    #[inline]
+    fn recover_with<R>(self, strategy: R) -> RecoverWith<Self, R, S, E>
+    where
+        Self: Sized,
+        R: Strategy<Self, S, E>,
+    {
+        RecoverWith {
+            parser: self,
+            strategy,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a parser terminated by a token.
+    ///
+    /// If the parser or the terminator fails, reports the error, then seeks from where
+    /// the parser started until the token is found (consuming it) and returns
+    /// `default()`. Seeking stops early at
+    /// the end of the stream, or at an unmatched closing delimiter if any delimiters
+    /// were added with [`nested`](Terminated::nested).
+    ///
+    /// If no input was consumed at all, the error is returned instead, so that
+    /// repetitions of this parser stop.
+    #[inline]
+    fn terminated<D>(self, token: S::Token, default: D) -> Terminated<Self, D, S, E>
+    where
+        Self: Sized,
+        D: FnMut() -> Self::Output,
+        S: BorrowState<State: Report<E>>,
+    {
+        Terminated {
+            parser: self,
+            token,
+            default,
+            delimiters: vec![],
+            _phantom: PhantomData,
+        }
+    }
 
     /// Set the cause for errors produced by this parser.
     #[inline]
@@ -346,7 +502,7 @@ where
 
     /// Take this parser by reference.
     #[inline]
-    fn by_ref(&mut self) -> ByRef<Self, S, E>
+    fn by_ref(&mut self) -> ByRef<'_, Self, S, E>
     where
         Self: Sized,
     {
@@ -356,6 +512,29 @@ where
         }
     }
 
+    /// Box this parser, erasing its type.
+    ///
+    /// Unlike [`opaque`](Parser::opaque), the resulting type can be named, so it can be
+    /// stored in struct fields and collections.
+    #[inline]
+    fn boxed<'a>(self) -> BoxedParser<'a, S, E, Self::Output>
+    where
+        Self: Sized + 'a,
+    {
+        BoxedParser::new(self)
+    }
+
+    /// Put this parser behind an [`Rc`](std::rc::Rc), erasing its type.
+    ///
+    /// The resulting parser can be cloned, with all clones sharing this parser.
+    #[inline]
+    fn rc<'a>(self) -> RcParser<'a, S, E, Self::Output>
+    where
+        Self: Sized + 'a,
+    {
+        RcParser::new(self)
+    }
+
     /// Hide the type of the parser.
     ///
     /// This is useful for debugging as it can simplify type errors,
@@ -370,6 +549,56 @@ where
     }
 }
 
+/// Run a parser to the end of the stream, taking the errors reported to the stream's
+/// [`ErrorCollector`].
+fn parse_collecting_errors<P, S, E>(parser: &mut P, mut stream: S) -> ParseResult<P::Output, E>
+where
+    P: Parser<S, E>,
+    S: Stream + BorrowState<State: AsMut<ErrorCollector<E>>>,
+    E: Error<S>,
+{
+    let result = parser
+        .parse(&mut stream)
+        .and_then(|output| end().parse(&mut stream).map(|()| output));
+
+    let collector = std::mem::take(stream.borrow_state().as_mut());
+    let mut errors = collector.into_errors();
+
+    let output = match result {
+        Ok(output) => Some(output),
+        Err(err) => {
+            errors.push(err);
+            None
+        }
+    };
+
+    ParseResult { output, errors }
+}
+
+/// The result of [`Parser::parse_with_errors`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseResult<O, E> {
+    /// The output, if parsing succeeded (possibly after recovering from errors).
+    pub output: Option<O>,
+    pub errors: Vec<E>,
+}
+
+impl<O, E> ParseResult<O, E> {
+    #[inline]
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// The output if there were no errors, or the errors otherwise.
+    #[inline]
+    pub fn into_result(self) -> Result<O, Vec<E>> {
+        match self.output {
+            Some(output) if self.errors.is_empty() => Ok(output),
+            _ => Err(self.errors),
+        }
+    }
+}
+
 impl<S, O, E, F> Parser<S, E> for F
 where
     S: Stream,
@@ -383,3 +612,60 @@ where
         self(stream)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{DefaultCause, DefaultError};
+    use crate::prelude::*;
+    use crate::report::StreamWithErrors;
+    use crate::stream::CharStream;
+
+    type Stream<'a> = StreamWithErrors<CharStream<'a>>;
+    type Error<'a> = DefaultError<Stream<'a>>;
+
+    #[test]
+    fn parse_complete_rejects_leftover_input() {
+        let mut parser = eat::<CharStream, DefaultError<CharStream>>('a');
+
+        assert_eq!(parser.parse_complete("a").ok(), Some('a'));
+
+        let err = parser.parse_complete("ab").unwrap_err();
+        assert_eq!(*err.span(), 1..2);
+        assert_eq!(*err.cause(), DefaultCause::ExpectedEnd);
+    }
+
+    #[test]
+    fn parse_with_errors_collects_recovered_errors() {
+        let mut parser = eat::<Stream, Error>('a')
+            .or_recover(eat('b'))
+            .retract_on_failure()
+            .repeat()
+            .collect::<String>();
+
+        let result = parser.parse_with_errors("aaa");
+        assert_eq!(result.output.as_deref(), Some("aaa"));
+        assert!(!result.has_errors());
+
+        let result = parser.parse_with_errors("aba");
+        assert_eq!(result.output.as_deref(), Some("aba"));
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(*result.errors[0].span(), 1..2);
+        assert!(result.into_result().is_err());
+    }
+
+    #[test]
+    fn parse_with_errors_puts_the_failure_last() {
+        let mut parser = eat::<Stream, Error>('a')
+            .or_recover(eat('b'))
+            .retract_on_failure()
+            .repeat()
+            .collect::<String>();
+
+        let result = parser.parse_with_errors("bac");
+        assert_eq!(result.output, None);
+
+        let spans: Vec<_> = result.errors.iter().map(|error| error.span()).collect();
+        assert_eq!(spans, [&(0..1), &(2..3)]);
+        assert_eq!(*result.errors[1].cause(), DefaultCause::ExpectedEnd);
+    }
+}
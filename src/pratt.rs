@@ -0,0 +1,616 @@
+//! Pratt (precedence climbing) parsing of prefix, infix and postfix operators.
+//!
+//! Operators are added to a [`Pratt`] parser with a binding power: operators with a
+//! higher binding power bind more tightly. Each operator is an ordinary parser, and
+//! is folded into the expression with a callback that receives the operator's output,
+//! its operands and the span of the whole expression.
+
+use std::marker::PhantomData;
+
+use derive_where::derive_where;
+
+use crate::error::Error;
+use crate::parser::Parser;
+use crate::stream::{Span, Stream};
+
+/// Create a Pratt parser for expressions made up of `atom`s.
+#[inline]
+pub fn pratt<Atom, S, E>(atom: Atom) -> Pratt<Atom, (), S, E>
+where
+    Atom: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    Pratt {
+        atom,
+        operators: (),
+        _phantom: PhantomData,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Associativity {
+    /// `a + b + c` is parsed as `(a + b) + c`.
+    Left,
+
+    /// `a ^ b ^ c` is parsed as `a ^ (b ^ c)`.
+    Right,
+
+    /// `a == b == c` is not allowed: parsing stops after `a == b`.
+    None,
+}
+
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash; Atom, Operators)]
+pub struct Pratt<Atom, Operators, S, E> {
+    atom: Atom,
+    operators: Operators,
+    _phantom: PhantomData<*const (S, E)>,
+}
+
+#[allow(clippy::type_complexity)]
+impl<Atom, Ops, S, E> Pratt<Atom, Ops, S, E>
+where
+    Atom: Parser<S, E>,
+    Ops: Operators<S, E, Atom::Output>,
+    S: Stream,
+    E: Error<S>,
+{
+    /// Add a prefix operator.
+    ///
+    /// `fold` is called with the operator's output, the operand and the span of
+    /// the whole expression.
+    #[inline]
+    pub fn prefix<P, F>(
+        self,
+        binding_power: u16,
+        parser: P,
+        fold: F,
+    ) -> Pratt<Atom, (Ops, Prefix<P, F, S, E>), S, E>
+    where
+        P: Parser<S, E>,
+        F: FnMut(P::Output, Atom::Output, S::Span) -> Atom::Output,
+    {
+        self.operator(Prefix {
+            parser,
+            fold,
+            binding_power,
+            pending: Vec::new(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Add an infix operator.
+    ///
+    /// `fold` is called with the left operand, the operator's output, the right operand
+    /// and the span of the whole expression.
+    #[inline]
+    pub fn infix<P, F>(
+        self,
+        associativity: Associativity,
+        binding_power: u16,
+        parser: P,
+        fold: F,
+    ) -> Pratt<Atom, (Ops, Infix<P, F, S, E>), S, E>
+    where
+        P: Parser<S, E>,
+        F: FnMut(Atom::Output, P::Output, Atom::Output, S::Span) -> Atom::Output,
+    {
+        self.operator(Infix {
+            parser,
+            fold,
+            associativity,
+            binding_power,
+            pending: Vec::new(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Add a postfix operator.
+    ///
+    /// `fold` is called with the operand, the operator's output and the span of
+    /// the whole expression.
+    #[inline]
+    pub fn postfix<P, F>(
+        self,
+        binding_power: u16,
+        parser: P,
+        fold: F,
+    ) -> Pratt<Atom, (Ops, Postfix<P, F, S, E>), S, E>
+    where
+        P: Parser<S, E>,
+        F: FnMut(Atom::Output, P::Output, S::Span) -> Atom::Output,
+    {
+        self.operator(Postfix {
+            parser,
+            fold,
+            binding_power,
+            pending: None,
+            _phantom: PhantomData,
+        })
+    }
+
+    #[inline]
+    fn operator<Op>(self, operator: Op) -> Pratt<Atom, (Ops, Op), S, E> {
+        Pratt {
+            atom: self.atom,
+            operators: (self.operators, operator),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn parse_expr(&mut self, stream: &mut S, min_power: u32) -> Result<(Atom::Output, S::Span), E>
+    where
+        S::Span: Clone,
+    {
+        let start_span = stream.peek_token_span();
+
+        let (mut lhs, mut lhs_span) = match self.operators.parse_prefix(stream) {
+            Some((index, power)) => {
+                let (operand, operand_span) = self.parse_expr(stream, power)?;
+                let span = start_span.merge_right(operand_span);
+                let output = self.operators.fold_prefix(index, operand, span.clone());
+                (output, span)
+            }
+
+            None => {
+                let output = self.atom.parse(stream)?;
+                let span = start_span.merge_right(stream.prev_token_span());
+                (output, span)
+            }
+        };
+
+        // The binding power of a non-associative operator that was just folded, which
+        // another operator of the same binding power can't follow.
+        let mut non_associative = None;
+
+        loop {
+            if let Some(index) = self.operators.parse_postfix(stream, min_power) {
+                non_associative = None;
+                lhs_span = lhs_span.merge_right(stream.prev_token_span());
+                lhs = self.operators.fold_postfix(index, lhs, lhs_span.clone());
+                continue;
+            }
+
+            if let Some((index, associativity, power)) =
+                self.operators
+                    .parse_infix(stream, min_power, non_associative)
+            {
+                let right_power = match associativity {
+                    Associativity::Right => power - 1,
+                    Associativity::Left | Associativity::None => power + 1,
+                };
+
+                let (rhs, rhs_span) = self.parse_expr(stream, right_power)?;
+                lhs_span = lhs_span.merge_right(rhs_span);
+                lhs = self.operators.fold_infix(index, lhs, rhs, lhs_span.clone());
+
+                non_associative = (associativity == Associativity::None).then_some(power);
+                continue;
+            }
+
+            break;
+        }
+
+        Ok((lhs, lhs_span))
+    }
+}
+
+impl<Atom, Ops, S, E> Parser<S, E> for Pratt<Atom, Ops, S, E>
+where
+    Atom: Parser<S, E>,
+    Ops: Operators<S, E, Atom::Output>,
+    S: Stream<Span: Clone>,
+    E: Error<S>,
+{
+    type Output = Atom::Output;
+
+    fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
+        // Operator outputs may have been left behind by a previous failed parse.
+        self.operators.clear_pending();
+
+        self.parse_expr(stream, 0).map(|(output, _)| output)
+    }
+}
+
+/// A set of operators for a [`Pratt`] parser.
+///
+/// This is implemented for `()` and for `(Operators, Operator)` pairs, which are
+/// built up by the methods on [`Pratt`].
+///
+/// Binding powers here are doubled, so that the binding power of an operator
+/// and of its operands can be distinguished.
+pub trait Operators<S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    const LEN: usize;
+
+    /// Try to parse a prefix operator, returning its index and binding power.
+    fn parse_prefix(&mut self, stream: &mut S) -> Option<(usize, u32)>;
+
+    /// Try to parse an infix operator that binds at least as tightly as `min_power`,
+    /// and whose binding power isn't `excluded`, returning its index, associativity
+    /// and binding power.
+    fn parse_infix(
+        &mut self,
+        stream: &mut S,
+        min_power: u32,
+        excluded: Option<u32>,
+    ) -> Option<(usize, Associativity, u32)>;
+
+    /// Try to parse a postfix operator that binds at least as tightly as `min_power`,
+    /// returning its index.
+    fn parse_postfix(&mut self, stream: &mut S, min_power: u32) -> Option<usize>;
+
+    fn fold_prefix(&mut self, index: usize, operand: O, span: S::Span) -> O;
+    fn fold_infix(&mut self, index: usize, lhs: O, rhs: O, span: S::Span) -> O;
+    fn fold_postfix(&mut self, index: usize, operand: O, span: S::Span) -> O;
+
+    fn clear_pending(&mut self);
+}
+
+impl<S, E, O> Operators<S, E, O> for ()
+where
+    S: Stream,
+    E: Error<S>,
+{
+    const LEN: usize = 0;
+
+    #[inline]
+    fn parse_prefix(&mut self, _stream: &mut S) -> Option<(usize, u32)> {
+        None
+    }
+
+    #[inline]
+    fn parse_infix(
+        &mut self,
+        _stream: &mut S,
+        _min_power: u32,
+        _excluded: Option<u32>,
+    ) -> Option<(usize, Associativity, u32)> {
+        None
+    }
+
+    #[inline]
+    fn parse_postfix(&mut self, _stream: &mut S, _min_power: u32) -> Option<usize> {
+        None
+    }
+
+    fn fold_prefix(&mut self, _index: usize, _operand: O, _span: S::Span) -> O {
+        unreachable!("no operator with this index")
+    }
+
+    fn fold_infix(&mut self, _index: usize, _lhs: O, _rhs: O, _span: S::Span) -> O {
+        unreachable!("no operator with this index")
+    }
+
+    fn fold_postfix(&mut self, _index: usize, _operand: O, _span: S::Span) -> O {
+        unreachable!("no operator with this index")
+    }
+
+    #[inline]
+    fn clear_pending(&mut self) {}
+}
+
+impl<Rest, Op, S, E, O> Operators<S, E, O> for (Rest, Op)
+where
+    Rest: Operators<S, E, O>,
+    Op: Operator<S, E, O>,
+    S: Stream,
+    E: Error<S>,
+{
+    const LEN: usize = Rest::LEN + 1;
+
+    #[inline]
+    fn parse_prefix(&mut self, stream: &mut S) -> Option<(usize, u32)> {
+        self.0
+            .parse_prefix(stream)
+            .or_else(|| self.1.parse_prefix(stream).map(|power| (Rest::LEN, power)))
+    }
+
+    #[inline]
+    fn parse_infix(
+        &mut self,
+        stream: &mut S,
+        min_power: u32,
+        excluded: Option<u32>,
+    ) -> Option<(usize, Associativity, u32)> {
+        self.0.parse_infix(stream, min_power, excluded).or_else(|| {
+            self.1
+                .parse_infix(stream, min_power, excluded)
+                .map(|(associativity, power)| (Rest::LEN, associativity, power))
+        })
+    }
+
+    #[inline]
+    fn parse_postfix(&mut self, stream: &mut S, min_power: u32) -> Option<usize> {
+        self.0
+            .parse_postfix(stream, min_power)
+            .or_else(|| self.1.parse_postfix(stream, min_power).then_some(Rest::LEN))
+    }
+
+    #[inline]
+    fn fold_prefix(&mut self, index: usize, operand: O, span: S::Span) -> O {
+        if index == Rest::LEN {
+            self.1.fold_prefix(operand, span)
+        } else {
+            self.0.fold_prefix(index, operand, span)
+        }
+    }
+
+    #[inline]
+    fn fold_infix(&mut self, index: usize, lhs: O, rhs: O, span: S::Span) -> O {
+        if index == Rest::LEN {
+            self.1.fold_infix(lhs, rhs, span)
+        } else {
+            self.0.fold_infix(index, lhs, rhs, span)
+        }
+    }
+
+    #[inline]
+    fn fold_postfix(&mut self, index: usize, operand: O, span: S::Span) -> O {
+        if index == Rest::LEN {
+            self.1.fold_postfix(operand, span)
+        } else {
+            self.0.fold_postfix(index, operand, span)
+        }
+    }
+
+    #[inline]
+    fn clear_pending(&mut self) {
+        self.0.clear_pending();
+        self.1.clear_pending();
+    }
+}
+
+/// A single operator of a [`Pratt`] parser.
+///
+/// Prefix and infix operators hold on to their output until their operands have been
+/// parsed, so that it can be passed to the fold callback.
+pub trait Operator<S, E, O>
+where
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    fn parse_prefix(&mut self, _stream: &mut S) -> Option<u32> {
+        None
+    }
+
+    #[inline]
+    fn parse_infix(
+        &mut self,
+        _stream: &mut S,
+        _min_power: u32,
+        _excluded: Option<u32>,
+    ) -> Option<(Associativity, u32)> {
+        None
+    }
+
+    #[inline]
+    fn parse_postfix(&mut self, _stream: &mut S, _min_power: u32) -> bool {
+        false
+    }
+
+    fn fold_prefix(&mut self, _operand: O, _span: S::Span) -> O {
+        unreachable!("not a prefix operator")
+    }
+
+    fn fold_infix(&mut self, _lhs: O, _rhs: O, _span: S::Span) -> O {
+        unreachable!("not an infix operator")
+    }
+
+    fn fold_postfix(&mut self, _operand: O, _span: S::Span) -> O {
+        unreachable!("not a postfix operator")
+    }
+
+    #[inline]
+    fn clear_pending(&mut self) {}
+}
+
+/// Run an operator's parser, rewinding the stream if it fails or binds too loosely.
+#[inline]
+fn parse_operator<P, S, E>(
+    parser: &mut P,
+    stream: &mut S,
+    power: u32,
+    min_power: u32,
+) -> Option<P::Output>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    if power < min_power {
+        return None;
+    }
+
+    let checkpoint = stream.checkpoint();
+    match parser.parse(stream) {
+        Ok(output) => Some(output),
+        Err(_) => {
+            stream.rewind(checkpoint);
+            None
+        }
+    }
+}
+
+#[derive_where(Debug, Clone; P, F, P::Output)]
+pub struct Prefix<P, F, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    parser: P,
+    fold: F,
+    binding_power: u16,
+    pending: Vec<P::Output>,
+    _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, F, S, E, O> Operator<S, E, O> for Prefix<P, F, S, E>
+where
+    P: Parser<S, E>,
+    F: FnMut(P::Output, O, S::Span) -> O,
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    fn parse_prefix(&mut self, stream: &mut S) -> Option<u32> {
+        let power = u32::from(self.binding_power) * 2 + 1;
+        let output = parse_operator(&mut self.parser, stream, power, 0)?;
+        self.pending.push(output);
+        Some(power)
+    }
+
+    #[inline]
+    fn fold_prefix(&mut self, operand: O, span: S::Span) -> O {
+        let output = self.pending.pop().expect("no pending operator");
+        (self.fold)(output, operand, span)
+    }
+
+    #[inline]
+    fn clear_pending(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[derive_where(Debug, Clone; P, F, P::Output)]
+pub struct Infix<P, F, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    parser: P,
+    fold: F,
+    associativity: Associativity,
+    binding_power: u16,
+    pending: Vec<P::Output>,
+    _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, F, S, E, O> Operator<S, E, O> for Infix<P, F, S, E>
+where
+    P: Parser<S, E>,
+    F: FnMut(O, P::Output, O, S::Span) -> O,
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    fn parse_infix(
+        &mut self,
+        stream: &mut S,
+        min_power: u32,
+        excluded: Option<u32>,
+    ) -> Option<(Associativity, u32)> {
+        let power = match self.associativity {
+            Associativity::Right => u32::from(self.binding_power) * 2 + 1,
+            Associativity::Left | Associativity::None => u32::from(self.binding_power) * 2,
+        };
+
+        if excluded == Some(power) {
+            return None;
+        }
+
+        let output = parse_operator(&mut self.parser, stream, power, min_power)?;
+        self.pending.push(output);
+        Some((self.associativity, power))
+    }
+
+    #[inline]
+    fn fold_infix(&mut self, lhs: O, rhs: O, span: S::Span) -> O {
+        let output = self.pending.pop().expect("no pending operator");
+        (self.fold)(lhs, output, rhs, span)
+    }
+
+    #[inline]
+    fn clear_pending(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[derive_where(Debug, Clone; P, F, P::Output)]
+pub struct Postfix<P, F, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    parser: P,
+    fold: F,
+    binding_power: u16,
+    pending: Option<P::Output>,
+    _phantom: PhantomData<*const (S, E)>,
+}
+
+impl<P, F, S, E, O> Operator<S, E, O> for Postfix<P, F, S, E>
+where
+    P: Parser<S, E>,
+    F: FnMut(O, P::Output, S::Span) -> O,
+    S: Stream,
+    E: Error<S>,
+{
+    #[inline]
+    fn parse_postfix(&mut self, stream: &mut S, min_power: u32) -> bool {
+        let power = u32::from(self.binding_power) * 2;
+
+        match parse_operator(&mut self.parser, stream, power, min_power) {
+            Some(output) => {
+                self.pending = Some(output);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[inline]
+    fn fold_postfix(&mut self, operand: O, span: S::Span) -> O {
+        let output = self.pending.take().expect("no pending operator");
+        (self.fold)(operand, output, span)
+    }
+
+    #[inline]
+    fn clear_pending(&mut self) {
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DefaultError;
+    use crate::prelude::*;
+    use crate::stream::CharStream;
+
+    fn expr<'a>(stream: &mut CharStream<'a>) -> Result<String, DefaultError<CharStream<'a>>> {
+        pratt(eat_if(char::is_ascii_digit).map(String::from))
+            .infix(Associativity::Left, 1, eat('|'), |lhs, _, rhs, _| {
+                format!("({lhs}|{rhs})")
+            })
+            .infix(Associativity::None, 2, eat('='), |lhs, _, rhs, _| {
+                format!("({lhs}={rhs})")
+            })
+            .parse(stream)
+    }
+
+    #[test]
+    fn non_associative_operator_allows_looser_operators() {
+        assert_eq!(
+            expr.parse_complete("1=2|3").ok().as_deref(),
+            Some("((1=2)|3)")
+        );
+        assert_eq!(
+            expr.parse_complete("1|2=3|4").ok().as_deref(),
+            Some("((1|(2=3))|4)")
+        );
+    }
+
+    #[test]
+    fn non_associative_operator_rejects_same_power() {
+        let err = expr.parse_complete("1=2=3").unwrap_err();
+        assert_eq!(*err.span(), 3..4);
+    }
+}
@@ -0,0 +1,345 @@
+//! Strategies for recovering from errors, for use with [`Parser::recover_with`].
+//!
+//! When a strategy recovers, the original error is reported to the stream's state
+//! and parsing continues. Otherwise, the stream is left where the parser failed and
+//! the original error is returned without being reported.
+//!
+//! [Fatal](Error::is_fatal) errors are never recovered from. Once the state's
+//! [limit](Report::limit_reached) is reached, recovering fails with a
+//! [too many errors](crate::error::Cause::too_many_errors) error, which is fatal for
+//! the default cause.
+
+use std::marker::PhantomData;
+
+use derive_where::derive_where;
+
+use crate::error::{Cause, Error, Report};
+use crate::parser::Parser;
+use crate::stream::{BorrowState, Span, Stream};
+
+/// A parser that failed, passed to a [`Strategy`].
+pub struct Failure<S: Stream, E> {
+    pub error: E,
+
+    /// Where the parser started.
+    pub start: S::Checkpoint,
+    pub start_span: S::Span,
+}
+
+/// A way to recover from a parser failing.
+pub trait Strategy<P, S, E>
+where
+    P: Parser<S, E>,
+    S: Stream,
+    E: Error<S>,
+{
+    /// Recover from a failure, with the stream left where the parser failed.
+    fn recover(
+        &mut self,
+        parser: &mut P,
+        stream: &mut S,
+        failure: Failure<S, E>,
+    ) -> Result<P::Output, E>;
+}
+
+/// Report the original error once a parser has recovered, failing if too many
+/// errors have been reported.
+#[inline]
+pub(crate) fn recovered<S, E>(stream: &mut S, error: E) -> Result<(), E>
+where
+    S: Stream + BorrowState<State: Report<E>>,
+    E: Error<S>,
+{
+    stream.borrow_state().report(error);
+
+    if stream.borrow_state().limit_reached() {
+        return Err(E::new(
+            E::Cause::too_many_errors(),
+            stream.peek_token_span(),
+        ));
+    }
+    Ok(())
+}
+
+/// Skip tokens until one of `tokens` is found outside of any of the `delimiters`
+/// pairs, leaving it unconsumed.
+///
+/// Returns `false` if the end of the stream or an unmatched closing delimiter is
+/// reached first.
+pub(crate) fn skip_balanced<S: Stream>(
+    stream: &mut S,
+    tokens: &[S::Token],
+    delimiters: &[(S::Token, S::Token)],
+) -> bool {
+    let mut closers = vec![];
+
+    while let Some(token) = stream.peek_token() {
+        if closers.is_empty() && tokens.contains(&token) {
+            return true;
+        }
+
+        if let Some(i) = delimiters.iter().position(|(open, _)| *open == token) {
+            closers.push(i);
+        } else if let Some(i) = delimiters.iter().position(|(_, close)| *close == token) {
+            if closers.pop() != Some(i) {
+                return false;
+            }
+        }
+
+        stream.next_token();
+    }
+
+    false
+}
+
+/// Skip tokens until one of `tokens` is found, consuming it and returning `fallback`
+/// called with the span of the skipped input.
+#[inline]
+pub fn skip_until<F, S>(tokens: impl IntoIterator<Item = S::Token>, fallback: F) -> SkipUntil<F, S>
+where
+    S: Stream,
+{
+    SkipUntil {
+        tokens: tokens.into_iter().collect(),
+        fallback,
+        _phantom: PhantomData,
+    }
+}
+
+#[derive_where(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash; F, S::Token)]
+pub struct SkipUntil<F, S: Stream> {
+    tokens: Vec<S::Token>,
+    fallback: F,
+    _phantom: PhantomData<*const S>,
+}
+
+impl<P, F, S, E> Strategy<P, S, E> for SkipUntil<F, S>
+where
+    P: Parser<S, E>,
+    F: FnMut(S::Span) -> P::Output,
+    S: Stream + BorrowState<State: Report<E>>,
+    E: Error<S>,
+{
+    fn recover(
+        &mut self,
+        _parser: &mut P,
+        stream: &mut S,
+        failure: Failure<S, E>,
+    ) -> Result<P::Output, E> {
+        let failed_at = stream.checkpoint();
+
+        if !skip_balanced(stream, &self.tokens, &[]) {
+            stream.rewind(failed_at);
+            return Err(failure.error);
+        }
+        stream.next_token();
+
+        recovered(stream, failure.error)?;
+        Ok((self.fallback)(
+            failure.start_span.merge_right(stream.prev_token_span()),
+        ))
+    }
+}
+
+/// Skip tokens one at a time from where the parser started, retrying the parser
+/// after each, until it succeeds.
+///
+/// Gives up at the end of the stream or when one of `tokens` is reached (without
+/// consuming it).
+#[inline]
+pub fn skip_then_retry_until<S>(tokens: impl IntoIterator<Item = S::Token>) -> SkipThenRetryUntil<S>
+where
+    S: Stream,
+{
+    SkipThenRetryUntil {
+        tokens: tokens.into_iter().collect(),
+    }
+}
+
+#[derive_where(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash; S::Token)]
+pub struct SkipThenRetryUntil<S: Stream> {
+    tokens: Vec<S::Token>,
+}
+
+impl<P, S, E> Strategy<P, S, E> for SkipThenRetryUntil<S>
+where
+    P: Parser<S, E>,
+    S: Stream + BorrowState<State: Report<E>>,
+    E: Error<S>,
+{
+    fn recover(
+        &mut self,
+        parser: &mut P,
+        stream: &mut S,
+        failure: Failure<S, E>,
+    ) -> Result<P::Output, E> {
+        let failed_at = stream.checkpoint();
+        stream.rewind(failure.start);
+
+        loop {
+            match stream.peek_token() {
+                Some(token) if !self.tokens.contains(&token) => {
+                    stream.next_token();
+                }
+                _ => break,
+            }
+
+            let checkpoint = stream.checkpoint();
+            match parser.parse(stream) {
+                Ok(output) => {
+                    recovered(stream, failure.error)?;
+                    return Ok(output);
+                }
+                Err(err) if err.is_fatal() => return Err(err),
+                Err(_) => stream.rewind(checkpoint),
+            }
+        }
+
+        stream.rewind(failed_at);
+        Err(failure.error)
+    }
+}
+
+/// Skip a balanced group of delimiters starting where the parser started, returning
+/// `fallback` called with the span of the group.
+///
+/// The group must start with `open` and end with a matching `close`. The `others`
+/// pairs are also kept balanced within it. Gives up if the parser didn't start at
+/// `open`, or if a closing delimiter doesn't match.
+#[inline]
+pub fn nested_delimiters<F, S>(
+    open: S::Token,
+    close: S::Token,
+    others: impl IntoIterator<Item = (S::Token, S::Token)>,
+    fallback: F,
+) -> NestedDelimiters<F, S>
+where
+    S: Stream,
+{
+    NestedDelimiters {
+        delimiters: std::iter::once((open, close)).chain(others).collect(),
+        fallback,
+        _phantom: PhantomData,
+    }
+}
+
+#[derive_where(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash; F, S::Token)]
+pub struct NestedDelimiters<F, S: Stream> {
+    /// The outer delimiters, followed by the others.
+    delimiters: Vec<(S::Token, S::Token)>,
+    fallback: F,
+    _phantom: PhantomData<*const S>,
+}
+
+impl<P, F, S, E> Strategy<P, S, E> for NestedDelimiters<F, S>
+where
+    P: Parser<S, E>,
+    F: FnMut(S::Span) -> P::Output,
+    S: Stream + BorrowState<State: Report<E>>,
+    E: Error<S>,
+{
+    fn recover(
+        &mut self,
+        _parser: &mut P,
+        stream: &mut S,
+        failure: Failure<S, E>,
+    ) -> Result<P::Output, E> {
+        let failed_at = stream.checkpoint();
+        stream.rewind(failure.start);
+
+        let (open, close) = &self.delimiters[0];
+        let balanced = stream.peek_token().is_some_and(|token| token == *open) && {
+            stream.next_token();
+            skip_balanced(stream, std::slice::from_ref(close), &self.delimiters)
+        };
+
+        if !balanced {
+            stream.rewind(failed_at);
+            return Err(failure.error);
+        }
+        stream.next_token();
+
+        recovered(stream, failure.error)?;
+        Ok((self.fallback)(
+            failure.start_span.merge_right(stream.prev_token_span()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::*;
+    use crate::error::{DefaultError, Error as _};
+    use crate::prelude::*;
+    use crate::report::{ErrorState, StreamWithErrors};
+    use crate::stream::CharStream;
+
+    type Stream<'a> = StreamWithErrors<CharStream<'a>>;
+    type Error<'a> = DefaultError<Stream<'a>>;
+
+    fn spans<'a>(errors: &'a [Error<'_>]) -> Vec<&'a Range<usize>> {
+        errors.iter().map(|error| error.cause_span()).collect()
+    }
+
+    fn pair<'a>() -> impl Parser<Stream<'a>, Error<'a>, Output = Range<usize>> {
+        chain!(eat('a'), eat('b')).map_to(0..0)
+    }
+
+    #[test]
+    fn skip_until_consumes_the_token() {
+        let mut parser = chain!(pair().recover_with(skip_until([';'], |span| span)), end());
+
+        let result = parser.parse_with_errors("ax;");
+        assert_eq!(result.output, Some((0..3, ())));
+        assert_eq!(spans(&result.errors), [&(1..2)]);
+
+        let result = parser.parse_with_errors("ax");
+        assert_eq!(result.output, None);
+        assert_eq!(spans(&result.errors), [&(1..2)]);
+    }
+
+    #[test]
+    fn skip_then_retry_until_retries_after_each_token() {
+        let mut parser =
+            eat_slice::<Stream, Error, _>("ab").recover_with(skip_then_retry_until([';']));
+
+        let result = parser.parse_with_errors("xxab");
+        assert_eq!(result.output, Some("ab"));
+        assert_eq!(spans(&result.errors), [&(0..1)]);
+
+        let result = parser.parse_with_errors("x;ab");
+        assert_eq!(result.output, None);
+        assert_eq!(spans(&result.errors), [&(0..1)]);
+    }
+
+    #[test]
+    fn nested_delimiters_skips_the_group() {
+        let group = chain!(eat('('), eat('a'), eat(')')).map_to(0..0);
+        let mut parser = chain!(
+            group.recover_with(nested_delimiters('(', ')', [('[', ']')], |span| span)),
+            end()
+        );
+
+        let result = parser.parse_with_errors("(b(c)[x])");
+        assert_eq!(result.output, Some((0..9, ())));
+        assert_eq!(spans(&result.errors), [&(1..2)]);
+
+        let result = parser.parse_with_errors("(b[)");
+        assert_eq!(result.output, None);
+        assert_eq!(spans(&result.errors), [&(1..2)]);
+    }
+
+    #[test]
+    fn recovering_stops_at_the_limit() {
+        let mut parser = pair()
+            .recover_with(skip_until([';'], |span| span))
+            .repeat()
+            .collect::<Vec<_>>();
+
+        let result = parser.parse_with_errors_in("x;y;ab;", ErrorState::default().with_limit(1));
+        assert_eq!(result.output, None);
+        assert!(result.errors.last().unwrap().is_fatal());
+    }
+}
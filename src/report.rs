@@ -0,0 +1,582 @@
+//! Collecting reported errors, and rendering [`DefaultError`]s against the source
+//! text they were produced from.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::Range;
+
+use crate::error::{Cause, DefaultError, ErrorSpan, Report};
+use crate::memo::{MemoSession, MemoState};
+use crate::stream::location::LineIndex;
+use crate::stream::{Rewind, Span, Stream, StreamWithState};
+
+/// Stream state that collects [reported](Report) errors, ordered by where they
+/// occurred.
+///
+/// An error at the same span as one already collected is discarded. Once the
+/// [limit](ErrorCollector::with_limit) is reached, further errors are discarded
+/// and parsers that recover from errors fail instead.
+///
+/// The collector can be the stream's state directly if the error type does not
+/// name the stream. For [`DefaultError`]s, which do, use a [`StreamWithErrors`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorCollector<E> {
+    /// Errors sorted by position, along with the order they were reported in.
+    errors: Vec<(usize, E)>,
+    limit: Option<usize>,
+}
+
+impl<E> Default for ErrorCollector<E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> ErrorCollector<E> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            errors: vec![],
+            limit: None,
+        }
+    }
+
+    /// Stop parsing once this many errors have been collected.
+    #[inline]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The collected errors, in order of position.
+    #[inline]
+    pub fn errors(&self) -> impl Iterator<Item = &E> {
+        self.errors.iter().map(|(_, error)| error)
+    }
+
+    /// Take the collected errors, in order of position.
+    #[inline]
+    pub fn into_errors(self) -> Vec<E> {
+        self.errors.into_iter().map(|(_, error)| error).collect()
+    }
+}
+
+impl<E: ErrorSpan<Span: PartialEq>> Report<E> for ErrorCollector<E> {
+    fn report(&mut self, error: E) {
+        if self.limit_reached() {
+            return;
+        }
+
+        let span = error.error_span();
+        let index = self
+            .errors
+            .partition_point(|(_, e)| e.error_span().cmp_start(span) != Ordering::Greater);
+
+        let duplicate = self.errors[..index]
+            .iter()
+            .rev()
+            .take_while(|(_, e)| e.error_span().cmp_start(span) == Ordering::Equal)
+            .any(|(_, e)| e.error_span() == span);
+
+        if !duplicate {
+            let order = self.errors.len();
+            self.errors.insert(index, (order, error));
+        }
+    }
+
+    #[inline]
+    fn limit_reached(&self) -> bool {
+        self.limit.is_some_and(|limit| self.errors.len() >= limit)
+    }
+}
+
+impl<E> AsMut<ErrorCollector<E>> for ErrorCollector<E> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut ErrorCollector<E> {
+        self
+    }
+}
+
+/// Rewinding discards the errors reported since the checkpoint.
+impl<E> Rewind for ErrorCollector<E> {
+    type Checkpoint = usize;
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.errors.len()
+    }
+
+    #[inline]
+    fn rewind(&mut self, checkpoint: Self::Checkpoint) {
+        if self.errors.len() > checkpoint {
+            self.errors.retain(|(order, _)| *order < checkpoint);
+        }
+    }
+}
+
+/// A stream whose state collects its [`DefaultError`]s, along with state of your
+/// own.
+pub type StreamWithErrors<S, State = ()> = StreamWithState<S, ErrorState<S, State>>;
+
+/// The state of a [`StreamWithErrors`]: an [`ErrorCollector`] for its
+/// [`DefaultError`]s, and state of your own.
+///
+/// A [`DefaultError`] names its stream, so a collector of them can't be the
+/// stream's state directly. This state breaks the cycle, and forwards [`Report`],
+/// [`Rewind`] and [`MemoState`] so that it can be used wherever they are needed.
+pub struct ErrorState<S: Stream, State: Rewind = ()> {
+    pub errors: ErrorCollector<DefaultError<StreamWithErrors<S, State>>>,
+    pub state: State,
+}
+
+impl<S: Stream, State: Rewind> ErrorState<S, State> {
+    #[inline]
+    pub fn new(state: State) -> Self {
+        Self {
+            errors: ErrorCollector::new(),
+            state,
+        }
+    }
+
+    /// Stop parsing once this many errors have been collected.
+    #[inline]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.errors = self.errors.with_limit(limit);
+        self
+    }
+}
+
+impl<S: Stream, State: Rewind + Default> Default for ErrorState<S, State> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(State::default())
+    }
+}
+
+impl<S, State> Clone for ErrorState<S, State>
+where
+    S: Stream<Token: Clone, Span: Clone> + Clone,
+    State: Rewind + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            errors: self.errors.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<S, State> fmt::Debug for ErrorState<S, State>
+where
+    S: Stream<Token: fmt::Debug, Slice: fmt::Debug, Span: fmt::Debug> + fmt::Debug,
+    State: Rewind + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorState")
+            .field("errors", &self.errors)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl<S, State> Report<DefaultError<StreamWithErrors<S, State>>> for ErrorState<S, State>
+where
+    S: Stream<Span: PartialEq>,
+    State: Rewind,
+{
+    #[inline]
+    fn report(&mut self, error: DefaultError<StreamWithErrors<S, State>>) {
+        self.errors.report(error);
+    }
+
+    #[inline]
+    fn limit_reached(&self) -> bool {
+        self.errors.limit_reached()
+    }
+}
+
+impl<S: Stream, State: Rewind> Rewind for ErrorState<S, State> {
+    type Checkpoint = (usize, State::Checkpoint);
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        (self.errors.checkpoint(), self.state.checkpoint())
+    }
+
+    #[inline]
+    fn rewind(&mut self, (errors, state): Self::Checkpoint) {
+        self.errors.rewind(errors);
+        self.state.rewind(state);
+    }
+}
+
+impl<S: Stream, State: Rewind> AsMut<ErrorCollector<DefaultError<StreamWithErrors<S, State>>>>
+    for ErrorState<S, State>
+{
+    #[inline]
+    fn as_mut(&mut self) -> &mut ErrorCollector<DefaultError<StreamWithErrors<S, State>>> {
+        &mut self.errors
+    }
+}
+
+impl<S: Stream, State: Rewind + MemoState> MemoState for ErrorState<S, State> {
+    #[inline]
+    fn memo_session(&self) -> MemoSession {
+        self.state.memo_session()
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// An error rendered against its source text, with line and column numbers, the
+/// offending source line and notes for any context the error was given.
+///
+/// Rendering is done through the [`Display`] implementation.
+pub struct Diagnostic<'a, S, C, Context>
+where
+    S: Stream,
+    C: Cause<S>,
+{
+    error: &'a DefaultError<S, C, Context>,
+    source: &'a str,
+    path: Option<&'a str>,
+    color: bool,
+}
+
+impl<'a, S, C, Context> Diagnostic<'a, S, C, Context>
+where
+    S: Stream<Span = Range<usize>>,
+    C: Cause<S> + Display,
+    Context: Display,
+{
+    #[inline]
+    pub fn new(error: &'a DefaultError<S, C, Context>, source: &'a str) -> Self {
+        Self {
+            error,
+            source,
+            path: None,
+            color: false,
+        }
+    }
+
+    /// Show a path before line and column numbers.
+    #[inline]
+    pub fn with_path(mut self, path: &'a str) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Use ANSI escape codes to colour the output.
+    #[inline]
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    #[inline]
+    fn style(&self, style: &'static str) -> &'static str {
+        if self.color {
+            style
+        } else {
+            ""
+        }
+    }
+
+    fn write_location(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        index: &LineIndex,
+        offset: usize,
+    ) -> fmt::Result {
+        let offset = offset.min(self.source.len());
+        let line = index.line(offset);
+        let line_start = index.line_range(line).unwrap().start;
+
+        // Columns are counted in characters, to match the carets under the snippet.
+        let column = self.source[line_start..offset].chars().count() + 1;
+
+        if let Some(path) = self.path {
+            write!(f, "{path}:")?;
+        }
+        write!(f, "{line}:{column}")
+    }
+
+    fn write_snippet(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        index: &LineIndex,
+        span: &Range<usize>,
+        gutter: usize,
+    ) -> fmt::Result {
+        let (blue, red, reset) = (self.style(BLUE), self.style(RED), self.style(RESET));
+
+        let line = index.line(span.start);
+        let range = index.line_range(line).unwrap();
+        let text = &self.source[range.clone()];
+
+        let start = span.start.clamp(range.start, range.end);
+        let end = span.end.clamp(start, range.end);
+
+        let padding = self.source[range.start..start].chars().count();
+        let width = self.source[start..end].chars().count().max(1);
+
+        writeln!(f, "{blue}{:gutter$} |{reset}", "")?;
+        writeln!(f, "{blue}{line:>gutter$} |{reset} {text}")?;
+        writeln!(
+            f,
+            "{blue}{:gutter$} |{reset} {:padding$}{red}{}{reset}",
+            "",
+            "",
+            "^".repeat(width)
+        )
+    }
+}
+
+impl<S, C, Context> Display for Diagnostic<'_, S, C, Context>
+where
+    S: Stream<Span = Range<usize>>,
+    C: Cause<S> + Display,
+    Context: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (bold, blue, red, reset) = (
+            self.style(BOLD),
+            self.style(BLUE),
+            self.style(RED),
+            self.style(RESET),
+        );
+
+        // Context is collected from the outside in, but reads better from the inside out.
+        let mut notes = vec![];
+        let mut error = self.error;
+        loop {
+            match error {
+                DefaultError::WithContext {
+                    context,
+                    span,
+                    inner,
+                } => {
+                    notes.push((Some(context), span));
+                    error = inner;
+                }
+
+                DefaultError::Recovering { from, inner } => {
+                    notes.push((None, from));
+                    error = inner;
+                }
+
+                _ => break,
+            }
+        }
+
+        let span = self.error.cause_span();
+        let index = LineIndex::new(self.source);
+
+        let gutter = notes
+            .iter()
+            .map(|(_, span)| span.start)
+            .chain([span.start])
+            .map(|offset| index.line(offset))
+            .max()
+            .unwrap_or(1)
+            .to_string()
+            .len();
+
+        writeln!(f, "{red}error{reset}{bold}: {}{reset}", self.error.cause())?;
+
+        write!(f, "{:gutter$}{blue}-->{reset} ", "")?;
+        self.write_location(f, &index, span.start)?;
+        writeln!(f)?;
+
+        self.write_snippet(f, &index, span, gutter)?;
+
+        for (context, span) in notes.into_iter().rev() {
+            write!(f, "{:gutter$} {blue}={reset} {bold}note{reset}: ", "")?;
+            match context {
+                Some(context) => write!(f, "{context} (at ")?,
+                None => write!(f, "while recovering from an earlier error (at ")?,
+            }
+            self.write_location(f, &index, span.start)?;
+            writeln!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::{Diagnostic, ErrorCollector, ErrorState, StreamWithErrors};
+    use crate::error::{
+        Cause, DefaultCause, DefaultError, Error, ErrorSpan, ErrorWithContext, Report,
+    };
+    use crate::prelude::*;
+    use crate::stream::{CharStream, Rewind, Stream, StreamWithState};
+
+    type Text<'a> = StreamWithErrors<CharStream<'a>>;
+    type TextError<'a> = DefaultError<Text<'a>>;
+
+    fn error_at(span: Range<usize>) -> TextError<'static> {
+        TextError::new(DefaultCause::custom("error"), span)
+    }
+
+    fn spans<'a>(collector: &'a ErrorCollector<TextError>) -> Vec<&'a Range<usize>> {
+        collector.errors().map(|error| error.cause_span()).collect()
+    }
+
+    #[test]
+    fn collector_orders_and_deduplicates() {
+        let mut collector = ErrorCollector::new();
+        for span in [4..5, 0..1, 4..6, 4..5, 2..3, 0..1] {
+            collector.report(error_at(span));
+        }
+
+        assert_eq!(spans(&collector), [&(0..1), &(2..3), &(4..5), &(4..6)]);
+    }
+
+    #[test]
+    fn collector_rewinds_in_report_order() {
+        let mut collector = ErrorCollector::new();
+        collector.report(error_at(4..5));
+
+        let checkpoint = collector.checkpoint();
+        collector.report(error_at(0..1));
+        collector.report(error_at(6..7));
+        assert_eq!(collector.len(), 3);
+
+        collector.rewind(checkpoint);
+        assert_eq!(spans(&collector), [&(4..5)]);
+    }
+
+    #[test]
+    fn error_state_clones_errors() {
+        let mut state = ErrorState::<CharStream, ()>::default();
+        state.report(error_at(0..1));
+
+        let copy = state.clone();
+        state.report(error_at(2..3));
+        assert_eq!(spans(&copy.errors), [&(0..1)]);
+    }
+
+    #[test]
+    fn error_state_stops_at_limit() {
+        let mut parser = eat::<Text, TextError>('a')
+            .terminated(';', || '?')
+            .repeat()
+            .collect::<Vec<_>>();
+
+        let result = parser.parse_with_errors("a;b;a;");
+        assert_eq!(result.output, Some(vec!['a', '?', 'a']));
+        assert_eq!(result.errors.len(), 1);
+
+        let result = parser.parse_with_errors_in("b;c;d;a;", ErrorState::default().with_limit(2));
+        assert_eq!(result.output, None);
+
+        let causes: Vec<_> = result.errors.iter().map(|error| error.cause()).collect();
+        assert_eq!(
+            causes,
+            [
+                &DefaultCause::ExpectedToken('a'),
+                &DefaultCause::ExpectedToken('a'),
+                &DefaultCause::TooManyErrors,
+            ]
+        );
+    }
+
+    struct AnyCause;
+
+    impl<S: Stream> Cause<S> for AnyCause {
+        fn expected_token(_token: S::Token) -> Self {
+            Self
+        }
+
+        fn expected_slice(_slice: &'static S::Slice) -> Self {
+            Self
+        }
+
+        fn expected_predicate() -> Self {
+            Self
+        }
+
+        fn expected_end() -> Self {
+            Self
+        }
+
+        fn unknown() -> Self {
+            Self
+        }
+    }
+
+    /// An error that does not name the stream, so that it can be collected by the
+    /// stream's own state.
+    #[derive(Debug, PartialEq)]
+    struct ErrorAt(Range<usize>);
+
+    impl<S: Stream<Span = Range<usize>>> Error<S> for ErrorAt {
+        type Cause = AnyCause;
+
+        fn new(_cause: Self::Cause, span: Range<usize>) -> Self {
+            Self(span)
+        }
+
+        fn set_cause(&mut self, _cause: Self::Cause) {}
+    }
+
+    impl ErrorSpan for ErrorAt {
+        type Span = Range<usize>;
+
+        fn error_span(&self) -> &Self::Span {
+            &self.0
+        }
+    }
+
+    type Input<'a> = StreamWithState<CharStream<'a>, ErrorCollector<ErrorAt>>;
+
+    #[test]
+    fn collector_as_state() {
+        let mut parser = eat::<Input, ErrorAt>('a')
+            .terminated(';', || '?')
+            .repeat()
+            .collect::<Vec<_>>();
+
+        let result = parser.parse_with_errors("a;b;a;ca;");
+        assert_eq!(result.output.map(|output| output.len()), Some(4));
+        assert_eq!(result.errors, [ErrorAt(2..3), ErrorAt(6..7)]);
+    }
+
+    #[test]
+    fn diagnostic_counts_columns_in_characters() {
+        let source = "a = 1;\nn\u{e4}me = ;\n";
+        let error: DefaultError<CharStream> = Error::new(DefaultCause::label("a number"), 15..16);
+        let error = error.with_context("an assignment".into(), 7..16);
+
+        assert_eq!(
+            Diagnostic::new(&error, source)
+                .with_path("a.txt")
+                .to_string(),
+            "error: expected a number\n \
+             --> a.txt:2:8\n  \
+             |\n\
+             2 | n\u{e4}me = ;\n  \
+             |        ^\n  \
+             = note: an assignment (at a.txt:2:1)\n"
+        );
+    }
+}
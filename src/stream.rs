@@ -1,15 +1,22 @@
-use std::ops::{Deref, Range};
+use std::cmp::Ordering;
+use std::num::NonZeroUsize;
+use std::ops::{Deref, DerefMut, Range};
 use std::slice::Iter;
 use std::str::Chars;
 
+pub mod location;
+pub mod reader;
+
 pub trait Stream {
     type Token: PartialEq;
 
     type Slice: ?Sized + 'static;
-    type SliceRef: Deref<Target = Self::Slice> + Copy;
+    type SliceRef: Deref<Target = Self::Slice> + Clone;
 
     type Span: Span;
 
+    type Checkpoint: Clone;
+
     fn peek_token(&self) -> Option<Self::Token>;
     fn next_token(&mut self) -> Option<Self::Token>;
 
@@ -21,6 +28,11 @@ pub trait Stream {
     where
         Self::Slice: PartialEq;
 
+    /// Get a slice of the stream between two positions.
+    ///
+    /// Streams that don't keep all of their input may only be able to slice input
+    /// after the oldest [checkpoint](Stream::checkpoint) or [pin](Stream::pin) that
+    /// is still alive.
     fn try_slice(&self, start: usize, end: usize) -> Option<Self::SliceRef>;
 
     fn peek_token_span(&self) -> Self::Span;
@@ -28,20 +40,98 @@ pub trait Stream {
 
     fn stream_position(&self) -> usize;
 
+    /// Save the current position of the stream so that it can be restored with
+    /// [`rewind`](Stream::rewind).
+    fn checkpoint(&self) -> Self::Checkpoint;
+
+    /// Restore the stream to a previously saved checkpoint.
+    fn rewind(&mut self, checkpoint: Self::Checkpoint);
+
+    /// Move the stream to a checkpoint, leaving any state as it is.
+    ///
+    /// This is used to replay a parse that started at the current position, without
+    /// undoing changes made to the state since. By default, this is the same as
+    /// [`rewind`](Stream::rewind).
+    #[inline]
+    fn jump_to(&mut self, checkpoint: Self::Checkpoint) {
+        self.rewind(checkpoint);
+    }
+
+    /// Keep the input from the current position on, so that it can be sliced,
+    /// until [`unpin`](Stream::unpin) is called with the returned position.
+    ///
+    /// This is cheaper than a [checkpoint](Stream::checkpoint) when the stream
+    /// doesn't need to be rewound. By default, it only returns the position, since
+    /// most streams keep all of their input.
+    #[inline]
+    fn pin(&self) -> usize {
+        self.stream_position()
+    }
+
+    /// Release input kept by [`pin`](Stream::pin).
+    #[inline]
+    fn unpin(&self, position: usize) {
+        let _ = position;
+    }
+
     #[inline]
     fn at_end(&self) -> bool {
         self.peek_token().is_none()
     }
 
+    /// Whether more input may follow the end of the stream.
+    ///
+    /// Parsers that run out of input on a partial stream report an
+    /// [incomplete](crate::error::Cause::incomplete) error rather than failing.
+    #[inline]
+    fn is_partial(&self) -> bool {
+        false
+    }
+
+    /// If the rest of the stream is a strict prefix of `slice`, the number of
+    /// tokens missing from the end of it.
+    ///
+    /// Streams that don't implement this can still be made [`Partial`], but
+    /// slices cut off by the end of the stream won't be reported as incomplete.
+    #[inline]
+    fn needed_for_slice(&self, slice: &Self::Slice) -> Option<NonZeroUsize>
+    where
+        Self::Slice: PartialEq,
+    {
+        let _ = slice;
+        None
+    }
+
+    /// Whether two slices are equal, for leaving out repeated expectations when
+    /// [causes are merged](crate::error::Cause::merge).
+    ///
+    /// By default, a slice is only equal to itself, at the same address.
+    #[inline]
+    fn slices_eq(a: &Self::Slice, b: &Self::Slice) -> bool
+    where
+        Self: Sized,
+    {
+        std::ptr::eq(a, b)
+    }
+
     #[inline]
     fn slice(&self, start: usize, end: usize) -> Self::SliceRef {
         self.try_slice(start, end).expect("slice out of bounds")
     }
 }
 
+/// A stream that can be created from some input, for the parse drivers such as
+/// [`Parser::parse_complete`](crate::parser::Parser::parse_complete).
+pub trait FromInput<I>: Stream {
+    fn from_input(input: I) -> Self;
+}
+
 pub trait Span {
     fn merge(self, other: Self) -> Self;
     fn merge_right(self, other: Self) -> Self;
+
+    /// Compare how far into the stream two spans start.
+    fn cmp_start(&self, other: &Self) -> Ordering;
 }
 
 impl<T: Ord> Span for Range<T> {
@@ -54,6 +144,11 @@ impl<T: Ord> Span for Range<T> {
     fn merge_right(self, other: Self) -> Self {
         self.start..self.end.max(other.end)
     }
+
+    #[inline]
+    fn cmp_start(&self, other: &Self) -> Ordering {
+        self.start.cmp(&other.start)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -72,6 +167,13 @@ impl<'a> CharStream<'a> {
     }
 }
 
+impl<'a> FromInput<&'a str> for CharStream<'a> {
+    #[inline]
+    fn from_input(input: &'a str) -> Self {
+        Self::new(input)
+    }
+}
+
 impl<'a> Stream for CharStream<'a> {
     type Token = char;
 
@@ -80,6 +182,8 @@ impl<'a> Stream for CharStream<'a> {
 
     type Span = Range<usize>;
 
+    type Checkpoint = Chars<'a>;
+
     #[inline]
     fn peek_token(&self) -> Option<Self::Token> {
         self.chars.clone().next()
@@ -109,6 +213,20 @@ impl<'a> Stream for CharStream<'a> {
         }
     }
 
+    #[inline]
+    fn needed_for_slice(&self, slice: &str) -> Option<NonZeroUsize> {
+        let rest = self.chars.as_str();
+        match slice.strip_prefix(rest) {
+            Some(missing) => NonZeroUsize::new(missing.chars().count()),
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn slices_eq(a: &str, b: &str) -> bool {
+        a == b
+    }
+
     #[inline]
     fn try_slice(&self, start: usize, end: usize) -> Option<Self::SliceRef> {
         self.all.get(start..end)
@@ -136,6 +254,127 @@ impl<'a> Stream for CharStream<'a> {
     fn stream_position(&self) -> usize {
         self.all.len() - self.chars.as_str().len()
     }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.chars.clone()
+    }
+
+    #[inline]
+    fn rewind(&mut self, checkpoint: Self::Checkpoint) {
+        self.chars = checkpoint;
+    }
+}
+
+/// A stream of bytes, for input that is binary or not necessarily UTF-8.
+#[derive(Debug, Clone)]
+pub struct ByteStream<'a> {
+    all: &'a [u8],
+    iter: Iter<'a, u8>,
+}
+
+impl<'a> ByteStream<'a> {
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            all: bytes,
+            iter: bytes.iter(),
+        }
+    }
+}
+
+impl<'a> FromInput<&'a [u8]> for ByteStream<'a> {
+    #[inline]
+    fn from_input(input: &'a [u8]) -> Self {
+        Self::new(input)
+    }
+}
+
+impl<'a> Stream for ByteStream<'a> {
+    type Token = u8;
+
+    type Slice = [u8];
+    type SliceRef = &'a [u8];
+
+    type Span = Range<usize>;
+
+    type Checkpoint = Iter<'a, u8>;
+
+    #[inline]
+    fn peek_token(&self) -> Option<Self::Token> {
+        self.iter.as_slice().first().copied()
+    }
+
+    #[inline]
+    fn next_token(&mut self) -> Option<Self::Token> {
+        self.iter.next().copied()
+    }
+
+    #[inline]
+    fn peek_slice(&self, slice: &[u8]) -> Option<Self::SliceRef> {
+        match self.iter.as_slice().split_at_checked(slice.len()) {
+            Some((prefix, _)) if prefix == slice => Some(prefix),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn eat_slice(&mut self, slice: &[u8]) -> Option<Self::SliceRef> {
+        match self.iter.as_slice().split_at_checked(slice.len()) {
+            Some((prefix, rest)) if prefix == slice => {
+                self.iter = rest.iter();
+                Some(prefix)
+            }
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn needed_for_slice(&self, slice: &[u8]) -> Option<NonZeroUsize> {
+        let rest = self.iter.as_slice();
+        match slice.strip_prefix(rest) {
+            Some(missing) => NonZeroUsize::new(missing.len()),
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn slices_eq(a: &[u8], b: &[u8]) -> bool {
+        a == b
+    }
+
+    #[inline]
+    fn try_slice(&self, start: usize, end: usize) -> Option<Self::SliceRef> {
+        self.all.get(start..end)
+    }
+
+    #[inline]
+    fn peek_token_span(&self) -> Range<usize> {
+        let pos = self.stream_position();
+        let len = usize::from(!self.at_end());
+        pos..(pos + len)
+    }
+
+    #[inline]
+    fn prev_token_span(&self) -> Range<usize> {
+        let pos = self.stream_position();
+        pos.saturating_sub(1)..pos
+    }
+
+    #[inline]
+    fn stream_position(&self) -> usize {
+        self.all.len() - self.iter.as_slice().len()
+    }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.iter.clone()
+    }
+
+    #[inline]
+    fn rewind(&mut self, checkpoint: Self::Checkpoint) {
+        self.iter = checkpoint;
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -156,6 +395,14 @@ impl<'a, T: AsToken> SliceStream<'a, T> {
     }
 }
 
+/// Created from the tokens and the span of the end of the stream.
+impl<'a, T: AsToken> FromInput<(&'a [T], T::Span)> for SliceStream<'a, T> {
+    #[inline]
+    fn from_input((slice, end): (&'a [T], T::Span)) -> Self {
+        Self::new(slice, end)
+    }
+}
+
 impl<'a, T: AsToken> Stream for SliceStream<'a, T> {
     type Token = T::Token;
 
@@ -164,6 +411,8 @@ impl<'a, T: AsToken> Stream for SliceStream<'a, T> {
 
     type Span = T::Span;
 
+    type Checkpoint = Iter<'a, T>;
+
     #[inline]
     fn peek_token(&self) -> Option<Self::Token> {
         self.iter.clone().next().map(|t| t.as_token())
@@ -199,6 +448,18 @@ impl<'a, T: AsToken> Stream for SliceStream<'a, T> {
         }
     }
 
+    #[inline]
+    fn needed_for_slice(&self, slice: &[T]) -> Option<NonZeroUsize>
+    where
+        Self::Slice: PartialEq,
+    {
+        let rest = self.iter.as_slice();
+        match slice.split_at_checked(rest.len()) {
+            Some((prefix, missing)) if prefix == rest => NonZeroUsize::new(missing.len()),
+            _ => None,
+        }
+    }
+
     #[inline]
     fn try_slice(&self, start: usize, end: usize) -> Option<Self::SliceRef> {
         self.all.get(start..end)
@@ -225,8 +486,21 @@ impl<'a, T: AsToken> Stream for SliceStream<'a, T> {
     fn stream_position(&self) -> usize {
         self.all.len() - self.iter.as_slice().len()
     }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.iter.clone()
+    }
+
+    #[inline]
+    fn rewind(&mut self, checkpoint: Self::Checkpoint) {
+        self.iter = checkpoint;
+    }
 }
 
+#[cfg(feature = "derive")]
+pub use parsley6_derive::AsToken;
+
 pub trait AsToken: 'static {
     type Token: PartialEq;
     type Span: Span + Clone;
@@ -235,6 +509,10 @@ pub trait AsToken: 'static {
     fn as_span(&self) -> Self::Span;
 }
 
+/// A stream along with some state of the parser's own.
+///
+/// The state is restored along with the stream when it is rewound, so it must
+/// implement [`Rewind`]. Wrap state that doesn't need this in [`NoRewind`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StreamWithState<S: Stream, State> {
     pub stream: S,
@@ -248,7 +526,166 @@ impl<S: Stream, State> StreamWithState<S, State> {
     }
 }
 
-impl<S: Stream, State> Stream for StreamWithState<S, State> {
+/// Created with the [default](Default) state.
+impl<I, S, State> FromInput<I> for StreamWithState<S, State>
+where
+    S: FromInput<I>,
+    State: Rewind + Default,
+{
+    #[inline]
+    fn from_input(input: I) -> Self {
+        Self::new(S::from_input(input), State::default())
+    }
+}
+
+impl<S: Stream, State: Rewind> Stream for StreamWithState<S, State> {
+    type Token = S::Token;
+
+    type Slice = S::Slice;
+    type SliceRef = S::SliceRef;
+
+    type Span = S::Span;
+
+    type Checkpoint = (S::Checkpoint, State::Checkpoint);
+
+    #[inline]
+    fn peek_token(&self) -> Option<Self::Token> {
+        self.stream.peek_token()
+    }
+
+    #[inline]
+    fn next_token(&mut self) -> Option<Self::Token> {
+        self.stream.next_token()
+    }
+
+    #[inline]
+    fn peek_slice(&self, slice: &Self::Slice) -> Option<Self::SliceRef>
+    where
+        Self::Slice: PartialEq,
+    {
+        self.stream.peek_slice(slice)
+    }
+
+    #[inline]
+    fn eat_slice(&mut self, slice: &Self::Slice) -> Option<Self::SliceRef>
+    where
+        Self::Slice: PartialEq,
+    {
+        self.stream.eat_slice(slice)
+    }
+
+    #[inline]
+    fn needed_for_slice(&self, slice: &Self::Slice) -> Option<NonZeroUsize>
+    where
+        Self::Slice: PartialEq,
+    {
+        self.stream.needed_for_slice(slice)
+    }
+
+    #[inline]
+    fn slices_eq(a: &Self::Slice, b: &Self::Slice) -> bool {
+        S::slices_eq(a, b)
+    }
+
+    #[inline]
+    fn try_slice(&self, start: usize, end: usize) -> Option<Self::SliceRef> {
+        self.stream.try_slice(start, end)
+    }
+
+    #[inline]
+    fn peek_token_span(&self) -> Self::Span {
+        self.stream.peek_token_span()
+    }
+
+    #[inline]
+    fn prev_token_span(&self) -> Self::Span {
+        self.stream.prev_token_span()
+    }
+
+    #[inline]
+    fn stream_position(&self) -> usize {
+        self.stream.stream_position()
+    }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        (self.stream.checkpoint(), self.state.checkpoint())
+    }
+
+    #[inline]
+    fn rewind(&mut self, (stream, state): Self::Checkpoint) {
+        self.stream.rewind(stream);
+        self.state.rewind(state);
+    }
+
+    #[inline]
+    fn jump_to(&mut self, (stream, _state): Self::Checkpoint) {
+        self.stream.jump_to(stream);
+    }
+
+    #[inline]
+    fn pin(&self) -> usize {
+        self.stream.pin()
+    }
+
+    #[inline]
+    fn unpin(&self, position: usize) {
+        self.stream.unpin(position);
+    }
+
+    #[inline]
+    fn at_end(&self) -> bool {
+        self.stream.at_end()
+    }
+
+    #[inline]
+    fn is_partial(&self) -> bool {
+        self.stream.is_partial()
+    }
+}
+
+/// A stream that may be followed by more input, such as a buffer that is filled
+/// from a socket or pipe.
+///
+/// Parsers that reach the end of a partial stream fail with an
+/// [incomplete](crate::error::Error::is_incomplete) error. Once more input
+/// has arrived, the parse can be retried from the start of a new stream. When no
+/// more input will follow, mark the stream [complete](Partial::complete) so that
+/// parsers fail normally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Partial<S: Stream> {
+    stream: S,
+    complete: bool,
+}
+
+impl<S: Stream> Partial<S> {
+    #[inline]
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            complete: false,
+        }
+    }
+
+    /// Mark the stream as complete, so that no more input is expected.
+    #[inline]
+    pub fn complete(mut self) -> Self {
+        self.complete = true;
+        self
+    }
+
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Stream> Stream for Partial<S> {
     type Token = S::Token;
 
     type Slice = S::Slice;
@@ -256,6 +693,8 @@ impl<S: Stream, State> Stream for StreamWithState<S, State> {
 
     type Span = S::Span;
 
+    type Checkpoint = S::Checkpoint;
+
     #[inline]
     fn peek_token(&self) -> Option<Self::Token> {
         self.stream.peek_token()
@@ -282,6 +721,19 @@ impl<S: Stream, State> Stream for StreamWithState<S, State> {
         self.stream.eat_slice(slice)
     }
 
+    #[inline]
+    fn needed_for_slice(&self, slice: &Self::Slice) -> Option<NonZeroUsize>
+    where
+        Self::Slice: PartialEq,
+    {
+        self.stream.needed_for_slice(slice)
+    }
+
+    #[inline]
+    fn slices_eq(a: &Self::Slice, b: &Self::Slice) -> bool {
+        S::slices_eq(a, b)
+    }
+
     #[inline]
     fn try_slice(&self, start: usize, end: usize) -> Option<Self::SliceRef> {
         self.stream.try_slice(start, end)
@@ -302,15 +754,127 @@ impl<S: Stream, State> Stream for StreamWithState<S, State> {
         self.stream.stream_position()
     }
 
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.stream.checkpoint()
+    }
+
+    #[inline]
+    fn rewind(&mut self, checkpoint: Self::Checkpoint) {
+        self.stream.rewind(checkpoint);
+    }
+
+    #[inline]
+    fn jump_to(&mut self, checkpoint: Self::Checkpoint) {
+        self.stream.jump_to(checkpoint);
+    }
+
+    #[inline]
+    fn pin(&self) -> usize {
+        self.stream.pin()
+    }
+
+    #[inline]
+    fn unpin(&self, position: usize) {
+        self.stream.unpin(position);
+    }
+
     #[inline]
     fn at_end(&self) -> bool {
         self.stream.at_end()
     }
+
+    #[inline]
+    fn is_partial(&self) -> bool {
+        !self.complete
+    }
+}
+
+/// State that can be restored when a [`StreamWithState`] is rewound.
+///
+/// Rewinding must restore the state as it was when the checkpoint was taken.
+/// Checkpoints are taken often, such as at every alternative, so they should be
+/// cheap: prefer keeping a journal of changes that can be undone over snapshotting
+/// the whole state. Wrap state that doesn't need to be restored in [`NoRewind`].
+pub trait Rewind {
+    type Checkpoint: Clone;
+
+    fn checkpoint(&self) -> Self::Checkpoint;
+    fn rewind(&mut self, checkpoint: Self::Checkpoint);
+}
+
+impl Rewind for () {
+    type Checkpoint = ();
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {}
+
+    #[inline]
+    fn rewind(&mut self, _checkpoint: Self::Checkpoint) {}
+}
+
+/// Checkpoints are the length of the [`Vec`], and rewinding truncates it.
+///
+/// This only undoes items being pushed, so treat the [`Vec`] as append-only while
+/// parsing: items that were removed or changed since the checkpoint aren't restored.
+impl<T> Rewind for Vec<T> {
+    type Checkpoint = usize;
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.len()
+    }
+
+    #[inline]
+    fn rewind(&mut self, checkpoint: Self::Checkpoint) {
+        self.truncate(checkpoint);
+    }
+}
+
+/// State that is left as it is when the stream is rewound.
+///
+/// This lets any type be used as the state of a [`StreamWithState`], for state that
+/// doesn't need to be restored when backtracking.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NoRewind<T>(pub T);
+
+impl<T> Rewind for NoRewind<T> {
+    type Checkpoint = ();
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {}
+
+    #[inline]
+    fn rewind(&mut self, _checkpoint: Self::Checkpoint) {}
+}
+
+impl<T> Deref for NoRewind<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for NoRewind<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
 }
 
 pub trait BorrowState: crate::sealed::Sealed {
     type State;
 
+    /// The stream without its state.
+    type Inner: Stream;
+
+    /// Combine a stream with a state.
+    fn from_parts(inner: Self::Inner, state: Self::State) -> Self
+    where
+        Self: Sized;
+
     fn borrow_state(&mut self) -> &mut Self::State;
 }
 
@@ -318,9 +882,86 @@ impl<S: Stream, State> crate::sealed::Sealed for StreamWithState<S, State> {}
 
 impl<S: Stream, State> BorrowState for StreamWithState<S, State> {
     type State = State;
+    type Inner = S;
+
+    #[inline]
+    fn from_parts(inner: Self::Inner, state: Self::State) -> Self {
+        Self::new(inner, state)
+    }
 
     #[inline]
     fn borrow_state(&mut self) -> &mut Self::State {
         &mut self.state
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vec_rewind_truncates() {
+        let mut state = vec![1, 2];
+        let checkpoint = state.checkpoint();
+
+        state.push(3);
+        state.push(4);
+
+        state.rewind(checkpoint);
+        assert_eq!(state, [1, 2]);
+    }
+
+    #[test]
+    fn no_rewind_keeps_state() {
+        let mut stream = StreamWithState::new(CharStream::new("ab"), NoRewind(0));
+        let checkpoint = stream.checkpoint();
+
+        stream.next_token();
+        *stream.state += 1;
+
+        stream.rewind(checkpoint);
+        assert_eq!(stream.stream_position(), 0);
+        assert_eq!(*stream.state, 1);
+    }
+
+    #[test]
+    fn char_stream_needs_missing_chars() {
+        let stream = CharStream::new("ab");
+        assert_eq!(stream.needed_for_slice("abéé"), NonZeroUsize::new(2));
+        assert_eq!(stream.needed_for_slice("ab"), None);
+        assert_eq!(stream.needed_for_slice("ax"), None);
+    }
+
+    #[test]
+    fn byte_stream_eats_slices() {
+        use crate::error::{DefaultCause, DefaultError, Expected};
+        use crate::prelude::*;
+
+        type Error<'a> = DefaultError<ByteStream<'a>>;
+
+        let mut header = chain!(
+            eat_slice::<ByteStream, Error, _>(b"GIF8"),
+            alt!(eat_slice(b"7a"), eat_slice(b"9a")),
+        )
+        .map(|(_, version)| version);
+
+        assert_eq!(header.parse_complete(&b"GIF89a"[..]).ok(), Some(&b"9a"[..]));
+
+        let err = header.parse_complete(&b"GIF88a"[..]).unwrap_err();
+        assert_eq!(*err.span(), 4..5);
+        assert_eq!(
+            *err.cause(),
+            DefaultCause::ExpectedOneOf(vec![
+                Expected::Slice(&b"7a"[..]),
+                Expected::Slice(&b"9a"[..])
+            ])
+        );
+
+        let mut stream = ByteStream::new(b"\xff12");
+        let result: Result<_, Error> =
+            prefixed(eat(0xff), eat_while(Ascii::is_ascii_digit)).parse(&mut stream);
+        assert_eq!(result.ok(), Some(&b"12"[..]));
+        assert!(stream.at_end());
+        assert_eq!(stream.prev_token_span(), 2..3);
+    }
+}
@@ -0,0 +1,334 @@
+//! Mapping byte offsets to human-readable line and column numbers.
+
+use std::cmp::Ordering;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::rc::Rc;
+
+use super::{CharStream, FromInput, Span, Stream};
+
+/// A 1-based line and column number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An index of the lines in a string, for converting byte offsets to line and
+/// column numbers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineIndex<'a> {
+    source: &'a str,
+
+    /// The byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    #[inline]
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// The number of lines in the source.
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The 1-based line containing a byte offset.
+    ///
+    /// Offsets past the end of the source are treated as being on the last line.
+    #[inline]
+    pub fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+
+    /// The byte range of a 1-based line, excluding its line ending.
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.source.len(), |next| next - 1);
+
+        let text = &self.source[start..end];
+        let end = start + text.strip_suffix('\r').unwrap_or(text).len();
+
+        Some(start..end)
+    }
+
+    /// The line and column of a byte offset, with the column counted in UTF-8 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is past the end of the source.
+    #[inline]
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        assert!(offset <= self.source.len(), "offset out of bounds");
+
+        let line = self.line(offset);
+        LineCol {
+            line,
+            column: offset - self.line_starts[line - 1] + 1,
+        }
+    }
+
+    /// The line and column of a byte offset, with the column counted in UTF-16
+    /// code units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is past the end of the source or is not on a character
+    /// boundary.
+    #[inline]
+    pub fn line_col_utf16(&self, offset: usize) -> LineCol {
+        let line = self.line(offset);
+        let text = &self.source[self.line_starts[line - 1]..offset];
+
+        LineCol {
+            line,
+            column: text.encode_utf16().count() + 1,
+        }
+    }
+}
+
+/// A span carrying line and column numbers (counted in UTF-8 bytes) as well
+/// as byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocatedSpan {
+    pub bytes: Range<usize>,
+    pub start: LineCol,
+    pub end: LineCol,
+}
+
+impl Span for LocatedSpan {
+    #[inline]
+    fn merge(self, other: Self) -> Self {
+        let (start, start_offset) = if other.bytes.start < self.bytes.start {
+            (other.start, other.bytes.start)
+        } else {
+            (self.start, self.bytes.start)
+        };
+
+        let (end, end_offset) = if other.bytes.end > self.bytes.end {
+            (other.end, other.bytes.end)
+        } else {
+            (self.end, self.bytes.end)
+        };
+
+        Self {
+            bytes: start_offset..end_offset,
+            start,
+            end,
+        }
+    }
+
+    #[inline]
+    fn merge_right(self, other: Self) -> Self {
+        let (end, end_offset) = if other.bytes.end > self.bytes.end {
+            (other.end, other.bytes.end)
+        } else {
+            (self.end, self.bytes.end)
+        };
+
+        Self {
+            bytes: self.bytes.start..end_offset,
+            start: self.start,
+            end,
+        }
+    }
+
+    #[inline]
+    fn cmp_start(&self, other: &Self) -> Ordering {
+        self.bytes.start.cmp(&other.bytes.start)
+    }
+}
+
+/// A [`CharStream`] whose spans carry line and column numbers.
+#[derive(Debug, Clone)]
+pub struct LocatedStream<'a> {
+    stream: CharStream<'a>,
+    index: Rc<LineIndex<'a>>,
+}
+
+impl<'a> LocatedStream<'a> {
+    #[inline]
+    pub fn new(s: &'a str) -> Self {
+        Self {
+            stream: CharStream::new(s),
+            index: Rc::new(LineIndex::new(s)),
+        }
+    }
+
+    #[inline]
+    pub fn line_index(&self) -> &LineIndex<'a> {
+        &self.index
+    }
+
+    #[inline]
+    fn locate(&self, bytes: Range<usize>) -> LocatedSpan {
+        LocatedSpan {
+            start: self.index.line_col(bytes.start),
+            end: self.index.line_col(bytes.end),
+            bytes,
+        }
+    }
+}
+
+impl<'a> FromInput<&'a str> for LocatedStream<'a> {
+    #[inline]
+    fn from_input(input: &'a str) -> Self {
+        Self::new(input)
+    }
+}
+
+impl<'a> Stream for LocatedStream<'a> {
+    type Token = char;
+
+    type Slice = str;
+    type SliceRef = &'a str;
+
+    type Span = LocatedSpan;
+
+    type Checkpoint = <CharStream<'a> as Stream>::Checkpoint;
+
+    #[inline]
+    fn peek_token(&self) -> Option<Self::Token> {
+        self.stream.peek_token()
+    }
+
+    #[inline]
+    fn next_token(&mut self) -> Option<Self::Token> {
+        self.stream.next_token()
+    }
+
+    #[inline]
+    fn peek_slice(&self, slice: &str) -> Option<Self::SliceRef> {
+        self.stream.peek_slice(slice)
+    }
+
+    #[inline]
+    fn eat_slice(&mut self, slice: &str) -> Option<Self::SliceRef> {
+        self.stream.eat_slice(slice)
+    }
+
+    #[inline]
+    fn needed_for_slice(&self, slice: &str) -> Option<NonZeroUsize> {
+        self.stream.needed_for_slice(slice)
+    }
+
+    #[inline]
+    fn slices_eq(a: &str, b: &str) -> bool {
+        a == b
+    }
+
+    #[inline]
+    fn try_slice(&self, start: usize, end: usize) -> Option<Self::SliceRef> {
+        self.stream.try_slice(start, end)
+    }
+
+    #[inline]
+    fn peek_token_span(&self) -> Self::Span {
+        self.locate(self.stream.peek_token_span())
+    }
+
+    #[inline]
+    fn prev_token_span(&self) -> Self::Span {
+        self.locate(self.stream.prev_token_span())
+    }
+
+    #[inline]
+    fn stream_position(&self) -> usize {
+        self.stream.stream_position()
+    }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.stream.checkpoint()
+    }
+
+    #[inline]
+    fn rewind(&mut self, checkpoint: Self::Checkpoint) {
+        self.stream.rewind(checkpoint);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DefaultError;
+    use crate::prelude::*;
+
+    #[test]
+    fn line_col_at_eof() {
+        let index = LineIndex::new("ab\ncd");
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(index.line_col(5), LineCol { line: 2, column: 3 });
+
+        let index = LineIndex::new("ab\n");
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(index.line_col(3), LineCol { line: 2, column: 1 });
+        assert_eq!(index.line_range(2), Some(3..3));
+        assert_eq!(index.line_range(3), None);
+
+        let index = LineIndex::new("");
+        assert_eq!(index.line_col(0), LineCol { line: 1, column: 1 });
+    }
+
+    #[test]
+    #[should_panic = "offset out of bounds"]
+    fn line_col_past_eof() {
+        LineIndex::new("ab").line_col(3);
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let index = LineIndex::new("ab\r\ncd\r\n");
+
+        assert_eq!(index.line_range(1), Some(0..2));
+        assert_eq!(index.line_range(2), Some(4..6));
+        assert_eq!(index.line_range(3), Some(8..8));
+
+        // The `\r` belongs to the line it ends.
+        assert_eq!(index.line_col(2), LineCol { line: 1, column: 3 });
+        assert_eq!(index.line_col(3), LineCol { line: 1, column: 4 });
+        assert_eq!(index.line_col(4), LineCol { line: 2, column: 1 });
+    }
+
+    #[test]
+    fn utf16_columns() {
+        let index = LineIndex::new("x\n\u{e9}\u{1f600}y");
+
+        assert_eq!(index.line_col(8), LineCol { line: 2, column: 7 });
+        assert_eq!(index.line_col_utf16(8), LineCol { line: 2, column: 4 });
+    }
+
+    #[test]
+    fn located_spans() {
+        let mut stream = LocatedStream::new("a\r\nbc");
+        let result: Result<_, DefaultError<LocatedStream>> =
+            chain!(eat('a'), eat_slice("\r\n"), eat('b')).parse(&mut stream);
+        assert!(result.is_ok());
+
+        assert_eq!(
+            stream.peek_token_span(),
+            LocatedSpan {
+                bytes: 4..5,
+                start: LineCol { line: 2, column: 2 },
+                end: LineCol { line: 2, column: 3 },
+            }
+        );
+    }
+}
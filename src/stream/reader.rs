@@ -0,0 +1,397 @@
+//! A stream that reads its input incrementally, for input too large to hold in memory.
+
+use std::cell::{RefCell, RefMut};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::ops::{Deref, Range};
+use std::rc::Rc;
+
+use super::{FromInput, Stream};
+
+/// The number of checkpoints and pins at each position in the input.
+type Pins = Rc<RefCell<BTreeMap<usize, usize>>>;
+
+#[inline]
+fn pin(pins: &Pins, position: usize) {
+    *pins.borrow_mut().entry(position).or_default() += 1;
+}
+
+#[inline]
+fn unpin(pins: &Pins, position: usize) {
+    let mut pins = pins.borrow_mut();
+    let count = pins.get_mut(&position).expect("position is pinned");
+
+    *count -= 1;
+    if *count == 0 {
+        pins.remove(&position);
+    }
+}
+
+/// A stream of bytes read from a [`BufRead`] as they are needed.
+///
+/// Input is held in a buffer that grows as the stream is read. Input before the
+/// current position is discarded once no [checkpoint](Stream::checkpoint) or
+/// [pin](Stream::pin) refers to it, so [`try_slice`](Stream::try_slice) only
+/// succeeds for input at or after the oldest of them. Slices share the buffer they were taken from, and
+/// keep it alive until they are dropped.
+///
+/// Positions and spans are byte offsets from the start of the input.
+///
+/// An I/O error ends the stream early. Check for one with
+/// [`take_io_error`](ReaderStream::take_io_error) after parsing.
+pub struct ReaderStream<R> {
+    buffer: RefCell<Buffer<R>>,
+    position: usize,
+    pins: Pins,
+}
+
+struct Buffer<R> {
+    reader: R,
+
+    /// Buffered input, starting at `start`.
+    data: Rc<Vec<u8>>,
+    start: usize,
+
+    eof: bool,
+    error: Option<io::Error>,
+}
+
+impl<R: BufRead> ReaderStream<R> {
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        Self {
+            buffer: RefCell::new(Buffer {
+                reader,
+                data: Rc::new(Vec::new()),
+                start: 0,
+                eof: false,
+                error: None,
+            }),
+            position: 0,
+            pins: Pins::default(),
+        }
+    }
+
+    /// Take the I/O error that ended the stream, if there was one.
+    #[inline]
+    pub fn take_io_error(&mut self) -> Option<io::Error> {
+        self.buffer.get_mut().error.take()
+    }
+
+    /// The range of the input that is currently buffered.
+    #[inline]
+    pub fn buffered(&self) -> Range<usize> {
+        let buffer = self.buffer.borrow();
+        buffer.start..(buffer.start + buffer.data.len())
+    }
+
+    /// Read until the input up to `end` is buffered, returning the buffer if it is.
+    #[inline]
+    fn fill_to(&self, end: usize) -> Option<RefMut<'_, Buffer<R>>> {
+        let mut buffer = self.buffer.borrow_mut();
+
+        if buffer.start + buffer.data.len() < end {
+            let keep_from = match self.pins.borrow().first_key_value() {
+                Some((&pinned, _)) => pinned.min(self.position),
+                None => self.position,
+            };
+            buffer.fill_to(end, keep_from);
+        }
+
+        (buffer.start + buffer.data.len() >= end).then_some(buffer)
+    }
+
+    #[inline]
+    fn slice_at(&self, slice: &[u8]) -> Option<ReaderSlice> {
+        let len = slice.len();
+        let buffer = self.fill_to(self.position + len)?;
+
+        let offset = self.position - buffer.start;
+        let range = offset..(offset + len);
+
+        (buffer.data[range.clone()] == *slice).then(|| ReaderSlice {
+            data: buffer.data.clone(),
+            range,
+        })
+    }
+}
+
+impl<R: BufRead> Buffer<R> {
+    fn fill_to(&mut self, end: usize, keep_from: usize) {
+        // Discard input that nothing refers to any more. If a slice still shares the
+        // buffer, the retained input is moved to a new one instead.
+        let discard = keep_from.saturating_sub(self.start).min(self.data.len());
+        if discard > 0 {
+            match Rc::get_mut(&mut self.data) {
+                Some(data) => {
+                    data.drain(..discard);
+                }
+                None => self.data = Rc::new(self.data[discard..].to_vec()),
+            }
+            self.start += discard;
+        }
+
+        while !self.eof && self.start + self.data.len() < end {
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    self.error = Some(err);
+                    self.eof = true;
+                    break;
+                }
+            };
+
+            if chunk.is_empty() {
+                self.eof = true;
+                break;
+            }
+
+            Rc::make_mut(&mut self.data).extend_from_slice(chunk);
+
+            let len = chunk.len();
+            self.reader.consume(len);
+        }
+    }
+}
+
+impl<R: BufRead> FromInput<R> for ReaderStream<R> {
+    #[inline]
+    fn from_input(input: R) -> Self {
+        Self::new(input)
+    }
+}
+
+impl<R> fmt::Debug for ReaderStream<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReaderStream")
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: BufRead> Stream for ReaderStream<R> {
+    type Token = u8;
+
+    type Slice = [u8];
+    type SliceRef = ReaderSlice;
+
+    type Span = Range<usize>;
+
+    type Checkpoint = ReaderCheckpoint;
+
+    #[inline]
+    fn peek_token(&self) -> Option<Self::Token> {
+        let buffer = self.fill_to(self.position + 1)?;
+        Some(buffer.data[self.position - buffer.start])
+    }
+
+    #[inline]
+    fn next_token(&mut self) -> Option<Self::Token> {
+        let token = self.peek_token()?;
+        self.position += 1;
+        Some(token)
+    }
+
+    #[inline]
+    fn peek_slice(&self, slice: &[u8]) -> Option<Self::SliceRef> {
+        self.slice_at(slice)
+    }
+
+    #[inline]
+    fn eat_slice(&mut self, slice: &[u8]) -> Option<Self::SliceRef> {
+        let slice = self.slice_at(slice)?;
+        self.position += slice.len();
+        Some(slice)
+    }
+
+    #[inline]
+    fn slices_eq(a: &[u8], b: &[u8]) -> bool {
+        a == b
+    }
+
+    #[inline]
+    fn try_slice(&self, start: usize, end: usize) -> Option<Self::SliceRef> {
+        let buffer = self.buffer.borrow();
+
+        let start = start.checked_sub(buffer.start)?;
+        let end = end.checked_sub(buffer.start)?;
+        buffer.data.get(start..end)?;
+
+        Some(ReaderSlice {
+            data: buffer.data.clone(),
+            range: start..end,
+        })
+    }
+
+    #[inline]
+    fn peek_token_span(&self) -> Range<usize> {
+        let pos = self.stream_position();
+        let len = usize::from(!self.at_end());
+        pos..(pos + len)
+    }
+
+    #[inline]
+    fn prev_token_span(&self) -> Range<usize> {
+        let pos = self.stream_position();
+        pos.saturating_sub(1)..pos
+    }
+
+    #[inline]
+    fn stream_position(&self) -> usize {
+        self.position
+    }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        ReaderCheckpoint::new(self.position, self.pins.clone())
+    }
+
+    #[inline]
+    fn rewind(&mut self, checkpoint: Self::Checkpoint) {
+        self.position = checkpoint.position;
+    }
+
+    #[inline]
+    fn pin(&self) -> usize {
+        pin(&self.pins, self.position);
+        self.position
+    }
+
+    #[inline]
+    fn unpin(&self, position: usize) {
+        unpin(&self.pins, position);
+    }
+}
+
+/// A slice of the input of a [`ReaderStream`].
+#[derive(Clone)]
+pub struct ReaderSlice {
+    data: Rc<Vec<u8>>,
+    range: Range<usize>,
+}
+
+impl Deref for ReaderSlice {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.data[self.range.clone()]
+    }
+}
+
+impl fmt::Debug for ReaderSlice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A checkpoint of a [`ReaderStream`], which keeps the input after it buffered
+/// for as long as it is alive.
+pub struct ReaderCheckpoint {
+    position: usize,
+    pins: Pins,
+}
+
+impl ReaderCheckpoint {
+    #[inline]
+    fn new(position: usize, pins: Pins) -> Self {
+        pin(&pins, position);
+        Self { position, pins }
+    }
+}
+
+impl Clone for ReaderCheckpoint {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.position, self.pins.clone())
+    }
+}
+
+impl Drop for ReaderCheckpoint {
+    #[inline]
+    fn drop(&mut self) {
+        unpin(&self.pins, self.position);
+    }
+}
+
+impl fmt::Debug for ReaderCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReaderCheckpoint")
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use super::*;
+    use crate::error::DefaultError;
+    use crate::prelude::*;
+
+    type Reader = ReaderStream<BufReader<&'static [u8]>>;
+
+    /// A stream that reads `chunk` bytes of `input` at a time.
+    fn reader(input: &'static str, chunk: usize) -> Reader {
+        ReaderStream::new(BufReader::with_capacity(chunk, input.as_bytes()))
+    }
+
+    #[test]
+    fn refills_across_chunks() {
+        let mut stream = reader("hello world", 3);
+
+        assert_eq!(stream.eat_slice(b"hello").as_deref(), Some(&b"hello"[..]));
+        assert_eq!(stream.next_token(), Some(b' '));
+        assert!(stream.peek_slice(b"world!").is_none());
+        assert_eq!(stream.eat_slice(b"world").as_deref(), Some(&b"world"[..]));
+        assert!(stream.at_end());
+        assert_eq!(stream.stream_position(), 11);
+    }
+
+    #[test]
+    fn slices_outlive_discarded_input() {
+        let mut stream = reader("abcdefgh", 2);
+        let slice = stream.eat_slice(b"abc").unwrap();
+
+        while stream.next_token().is_some() {}
+        assert!(stream.buffered().start > 0);
+        assert!(stream.try_slice(0, 3).is_none());
+        assert_eq!(&*slice, b"abc");
+    }
+
+    #[test]
+    fn rewinds_to_pinned_checkpoint() {
+        let mut stream = reader("abcdefgh", 2);
+        stream.next_token();
+
+        let checkpoint = stream.checkpoint();
+        while stream.next_token().is_some() {}
+        assert_eq!(stream.buffered().start, 1);
+        assert_eq!(stream.try_slice(1, 8).as_deref(), Some(&b"bcdefgh"[..]));
+
+        stream.rewind(checkpoint);
+        assert_eq!(stream.next_token(), Some(b'b'));
+        assert!(stream.pins.borrow().is_empty());
+    }
+
+    #[test]
+    fn pins_keep_input_until_released() {
+        let mut stream = reader("aaaaaab", 2);
+
+        let slice = eat_while::<_, _, DefaultError<Reader>>(|&b: &u8| b == b'a')
+            .parse(&mut stream)
+            .ok()
+            .unwrap();
+        assert_eq!(&*slice, b"aaaaaa");
+        assert!(stream.pins.borrow().is_empty());
+
+        let start = stream.pin();
+        stream.next_token();
+        assert_eq!(stream.try_slice(start, 7).as_deref(), Some(&b"b"[..]));
+        stream.unpin(start);
+        assert!(stream.pins.borrow().is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 pub mod text;
 
 use std::marker::PhantomData;
+use std::num::NonZeroUsize;
 
 use derive_where::derive_where;
 
@@ -8,6 +9,40 @@ use crate::error::{Cause, Error};
 use crate::parser::Parser;
 use crate::stream::Stream;
 
+/// An error for a token that didn't match, which is
+/// [incomplete](Cause::incomplete) if the end of a partial stream was reached.
+#[inline]
+fn unexpected<S, E>(stream: &S, cause: impl FnOnce() -> E::Cause) -> E
+where
+    S: Stream,
+    E: Error<S>,
+{
+    let cause = if stream.is_partial() && stream.at_end() {
+        E::Cause::incomplete(NonZeroUsize::new(1))
+    } else {
+        cause()
+    };
+
+    E::new(cause, stream.peek_token_span())
+}
+
+/// An error for a slice that didn't match, which is
+/// [incomplete](Cause::incomplete) if the slice was cut off by the end of a
+/// partial stream.
+#[inline]
+fn unexpected_slice<S, E>(stream: &S, slice: &'static S::Slice) -> E
+where
+    S: Stream<Slice: PartialEq>,
+    E: Error<S>,
+{
+    let cause = match stream.needed_for_slice(slice) {
+        Some(needed) if stream.is_partial() => E::Cause::incomplete(Some(needed)),
+        _ => E::Cause::expected_slice(slice),
+    };
+
+    E::new(cause, stream.peek_token_span())
+}
+
 /// Match a token without consuming it, or return an error.
 #[inline]
 pub fn peek<S, E>(token: S::Token) -> Peek<S, E>
@@ -38,10 +73,9 @@ where
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
         match stream.peek_token() {
             Some(token) if self.token == token => Ok(token),
-            _ => Err(E::new(
-                E::Cause::expected_token(self.token.clone()),
-                stream.peek_token_span(),
-            )),
+            _ => Err(unexpected(stream, || {
+                E::Cause::expected_token(self.token.clone())
+            })),
         }
     }
 }
@@ -79,10 +113,9 @@ where
                 stream.next_token();
                 Ok(token)
             }
-            _ => Err(E::new(
-                E::Cause::expected_token(self.token.clone()),
-                stream.peek_token_span(),
-            )),
+            _ => Err(unexpected(stream, || {
+                E::Cause::expected_token(self.token.clone())
+            })),
         }
     }
 }
@@ -119,10 +152,7 @@ where
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
         match stream.peek_token() {
             Some(token) if (self.f)(&token) => Ok(token),
-            _ => Err(E::new(
-                E::Cause::expected_predicate(),
-                stream.peek_token_span(),
-            )),
+            _ => Err(unexpected(stream, E::Cause::expected_predicate)),
         }
     }
 }
@@ -162,23 +192,21 @@ where
                 stream.next_token();
                 Ok(token)
             }
-            _ => Err(E::new(
-                E::Cause::expected_predicate(),
-                stream.peek_token_span(),
-            )),
+            _ => Err(unexpected(stream, E::Cause::expected_predicate)),
         }
     }
 }
 
 /// Match a slice without consuming it, or return an error.
 #[inline]
-pub fn peek_slice<S, E>(slice: &'static S::Slice) -> PeekSlice<S, E>
+pub fn peek_slice<S, E, Slice>(slice: &'static Slice) -> PeekSlice<S, E>
 where
     S: Stream<Slice: PartialEq>,
     E: Error<S>,
+    Slice: AsRef<S::Slice> + ?Sized,
 {
     PeekSlice {
-        slice,
+        slice: slice.as_ref(),
         _phantom: PhantomData,
     }
 }
@@ -198,24 +226,23 @@ where
 
     #[inline]
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
-        stream.peek_slice(self.slice).ok_or_else(|| {
-            E::new(
-                E::Cause::expected_slice(self.slice),
-                stream.peek_token_span(),
-            )
-        })
+        match stream.peek_slice(self.slice) {
+            Some(slice) => Ok(slice),
+            None => Err(unexpected_slice(stream, self.slice)),
+        }
     }
 }
 
 /// Consume a slice if it matches, or return an error.
 #[inline]
-pub fn eat_slice<S, E>(slice: &'static S::Slice) -> EatSlice<S, E>
+pub fn eat_slice<S, E, Slice>(slice: &'static Slice) -> EatSlice<S, E>
 where
     S: Stream<Slice: PartialEq>,
     E: Error<S>,
+    Slice: AsRef<S::Slice> + ?Sized,
 {
     EatSlice {
-        slice,
+        slice: slice.as_ref(),
         _phantom: PhantomData,
     }
 }
@@ -235,12 +262,10 @@ where
 
     #[inline]
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
-        stream.eat_slice(self.slice).ok_or_else(|| {
-            E::new(
-                E::Cause::expected_slice(self.slice),
-                stream.peek_token_span(),
-            )
-        })
+        match stream.eat_slice(self.slice) {
+            Some(slice) => Ok(slice),
+            None => Err(unexpected_slice(stream, self.slice)),
+        }
     }
 }
 
@@ -271,7 +296,9 @@ where
 
     #[inline]
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
-        if stream.at_end() {
+        if stream.at_end() && stream.is_partial() {
+            Err(E::new(E::Cause::incomplete(None), stream.peek_token_span()))
+        } else if stream.at_end() {
             Ok(())
         } else {
             Err(E::new(E::Cause::expected_end(), stream.peek_token_span()))
@@ -309,12 +336,20 @@ where
 
     #[inline]
     fn parse(&mut self, stream: &mut S) -> Result<Self::Output, E> {
-        let start = stream.stream_position();
+        // Keep the input being sliced from being discarded.
+        let start = stream.pin();
         while stream.peek_token().is_some_and(|t| (self.f)(&t)) {
             stream.next_token();
         }
-        let end = stream.stream_position();
-        Ok(stream.slice(start, end))
+
+        let result = if stream.at_end() && stream.is_partial() {
+            Err(E::new(E::Cause::incomplete(None), stream.peek_token_span()))
+        } else {
+            Ok(stream.slice(start, stream.stream_position()))
+        };
+
+        stream.unpin(start);
+        result
     }
 }
 
@@ -372,9 +407,6 @@ where
             stream.next_token();
         }
 
-        Err(E::new(
-            E::Cause::expected_predicate(),
-            stream.peek_token_span(),
-        ))
+        Err(unexpected(stream, E::Cause::expected_predicate))
     }
 }
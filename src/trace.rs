@@ -0,0 +1,213 @@
+//! Tracing of parsers wrapped with [`Parser::trace`](crate::parser::Parser::trace).
+//!
+//! Events are sent to a per-thread [`TraceSink`], which prints an indented tree to
+//! stderr by default.
+
+use std::cell::{Cell, RefCell};
+
+/// A traced parser starting or finishing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceEvent {
+    Enter {
+        name: &'static str,
+        depth: usize,
+        position: usize,
+    },
+
+    Exit {
+        name: &'static str,
+        depth: usize,
+        start: usize,
+        end: usize,
+        success: bool,
+    },
+}
+
+pub trait TraceSink {
+    fn event(&mut self, event: TraceEvent);
+}
+
+impl<F: FnMut(TraceEvent)> TraceSink for F {
+    #[inline]
+    fn event(&mut self, event: TraceEvent) {
+        self(event)
+    }
+}
+
+/// Prints events to stderr as a tree, indented by depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct StderrSink;
+
+impl TraceSink for StderrSink {
+    fn event(&mut self, event: TraceEvent) {
+        match event {
+            TraceEvent::Enter {
+                name,
+                depth,
+                position,
+            } => eprintln!("{:indent$}{name} @ {position}", "", indent = depth * 2),
+
+            TraceEvent::Exit {
+                name,
+                depth,
+                start,
+                end,
+                success,
+            } => {
+                let outcome = if success { "ok" } else { "err" };
+                eprintln!(
+                    "{:indent$}{name} @ {start}..{end} {outcome}",
+                    "",
+                    indent = depth * 2
+                );
+            }
+        }
+    }
+}
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    /// Taken while an event is being sent, so that a sink can trace parsers itself.
+    static SINK: RefCell<Option<Box<dyn TraceSink>>> = RefCell::new(Some(Box::new(StderrSink)));
+}
+
+/// Send this thread's trace events to a sink, returning the previous sink.
+///
+/// If called from within a sink, the running sink is dropped once it returns, and a
+/// sink that does nothing is returned in its place.
+pub fn set_sink(sink: impl TraceSink + 'static) -> Box<dyn TraceSink> {
+    SINK.with_borrow_mut(|slot| slot.replace(Box::new(sink)))
+        .unwrap_or_else(|| Box::new(|_| {}))
+}
+
+/// Send an event to the sink, unless it is the sink itself that is being traced.
+fn send(event: TraceEvent) {
+    let Some(mut sink) = SINK.with_borrow_mut(Option::take) else {
+        return;
+    };
+
+    sink.event(event);
+
+    SINK.with_borrow_mut(|slot| {
+        // The sink may have replaced itself.
+        slot.get_or_insert(sink);
+    });
+}
+
+/// Restores the depth when a traced parser finishes, even if it panics.
+pub(crate) struct Guard {
+    name: &'static str,
+    depth: usize,
+    start: usize,
+}
+
+impl Guard {
+    pub(crate) fn exit(self, end: usize, success: bool) {
+        DEPTH.set(self.depth);
+
+        send(TraceEvent::Exit {
+            name: self.name,
+            depth: self.depth,
+            start: self.start,
+            end,
+            success,
+        });
+    }
+}
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        DEPTH.set(self.depth);
+    }
+}
+
+pub(crate) fn enter(name: &'static str, position: usize) -> Guard {
+    let depth = DEPTH.replace(DEPTH.get() + 1);
+
+    send(TraceEvent::Enter {
+        name,
+        depth,
+        position,
+    });
+
+    Guard {
+        name,
+        depth,
+        start: position,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::error::DefaultError;
+    use crate::prelude::*;
+    use crate::stream::CharStream;
+
+    type Error<'a> = DefaultError<CharStream<'a>>;
+
+    fn record() -> Rc<RefCell<Vec<TraceEvent>>> {
+        let events = Rc::new(RefCell::new(vec![]));
+        let sink_events = events.clone();
+        set_sink(move |event| sink_events.borrow_mut().push(event));
+        events
+    }
+
+    #[test]
+    fn depth_is_restored_after_panic() {
+        let events = record();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut parser = (|_: &mut CharStream| -> Result<(), Error> { panic!("oops") })
+                .trace("inner")
+                .trace("outer");
+            let _ = parser.parse(&mut CharStream::new(""));
+        }));
+        assert!(result.is_err());
+
+        events.borrow_mut().clear();
+        let _ = eat::<CharStream, Error>('a')
+            .trace("after")
+            .parse(&mut CharStream::new("a"));
+
+        assert!(matches!(
+            events.borrow()[0],
+            TraceEvent::Enter {
+                name: "after",
+                depth: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn sink_can_trace_parsers() {
+        let events = Rc::new(RefCell::new(vec![]));
+        let sink_events = events.clone();
+        set_sink(move |event| {
+            let _ = eat::<CharStream, Error>('a')
+                .trace("in sink")
+                .parse(&mut CharStream::new("a"));
+            sink_events.borrow_mut().push(event);
+        });
+
+        let _ = eat::<CharStream, Error>('a')
+            .trace("outer")
+            .parse(&mut CharStream::new("a"));
+
+        let names: Vec<_> = events
+            .borrow()
+            .iter()
+            .map(|event| match event {
+                TraceEvent::Enter { name, .. } | TraceEvent::Exit { name, .. } => *name,
+            })
+            .collect();
+        assert_eq!(names, ["outer", "outer"]);
+    }
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "derive")]
+
+use std::ops::Range;
+
+use parsley6::prelude::*;
+
+use parsley6::error::DefaultError;
+use parsley6::stream::{AsToken, SliceStream};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Let,
+    Ident(Box<str>),
+    Number(u32),
+    Semicolon,
+}
+
+#[derive(Debug, AsToken)]
+struct Token {
+    #[token(span)]
+    span: Range<usize>,
+    #[token(kind)]
+    kind: Kind,
+}
+
+#[derive(Debug, AsToken)]
+struct Pair(Kind, Range<usize>);
+
+fn tokens() -> Vec<Token> {
+    [
+        (Kind::Let, 0..3),
+        (Kind::Ident("x".into()), 4..5),
+        (Kind::Number(1), 8..9),
+        (Kind::Semicolon, 9..10),
+    ]
+    .into_iter()
+    .map(|(kind, span)| Token { span, kind })
+    .collect()
+}
+
+#[test]
+fn named_fields() {
+    let token = Token {
+        span: 4..5,
+        kind: Kind::Ident("x".into()),
+    };
+
+    assert_eq!(token.as_token(), Kind::Ident("x".into()));
+    assert_eq!(token.as_span(), 4..5);
+}
+
+#[test]
+fn tuple_struct() {
+    let token = Pair(Kind::Number(7), 2..3);
+
+    assert_eq!(token.as_token(), Kind::Number(7));
+    assert_eq!(token.as_span(), 2..3);
+}
+
+#[test]
+fn parses_derived_tokens() {
+    type Tokens<'a> = SliceStream<'a, Token>;
+
+    let tokens = tokens();
+    let mut stream = Tokens::new(&tokens, 10..10);
+
+    let name = eat_if(|kind: &Kind| matches!(kind, Kind::Ident(_))).map(|kind| match kind {
+        Kind::Ident(name) => name,
+        _ => unreachable!(),
+    });
+
+    let result: Result<_, DefaultError<Tokens<'_>>> = chain!(
+        eat(Kind::Let),
+        name,
+        eat(Kind::Number(1)),
+        eat(Kind::Semicolon),
+        end()
+    )
+    .map(|(_, name, ..)| name)
+    .parse(&mut stream);
+
+    assert_eq!(result.unwrap(), "x".into());
+
+    // Data variants only match tokens carrying the same data.
+    let mut stream = Tokens::new(&tokens[2..], 10..10);
+    let result: Result<_, DefaultError<Tokens<'_>>> = eat(Kind::Number(2)).parse(&mut stream);
+    assert_eq!(result.unwrap_err().span(), &(8..9));
+}